@@ -8,7 +8,10 @@ pub(crate) struct Config {
     pub monitor_config: tgfeed_monitor::Config,
     pub bot_config: tgfeed_bot::Config,
     pub repo_config: tgfeed_repo::Config,
+    #[serde(default)]
+    pub retention_config: tgfeed_repo::RetentionConfig,
     pub ai_config: tgfeed_ai::Config,
+    pub api_config: tgfeed_api::Config,
 }
 
 impl Default for Config {
@@ -0,0 +1,189 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use tgfeed_common::command::MonitorCommand;
+use tokio::sync::mpsc;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub(crate) struct HealthState {
+    pub repo: tgfeed_repo::Repo,
+    pub monitor: tgfeed_monitor::MonitorStatus,
+    pub bot: tgfeed_bot::BotStatus,
+    // weak sender so the health server never keeps the channel open
+    pub monitor_tx: mpsc::WeakSender<MonitorCommand>,
+}
+
+#[derive(serde::Serialize)]
+struct HealthReport {
+    healthy: bool,
+    components: Components,
+    channels: Channels,
+}
+
+#[derive(serde::Serialize)]
+struct Components {
+    storage: ComponentStatus,
+    mtproto: ComponentStatus,
+    monitor: ComponentStatus,
+    bot_dispatcher: ComponentStatus,
+    monitor_events: ComponentStatus,
+}
+
+#[derive(serde::Serialize)]
+struct ComponentStatus {
+    healthy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct Channels {
+    monitor_commands: Option<ChannelDepth>,
+    bot_events: Option<QueueDepth>,
+}
+
+#[derive(serde::Serialize)]
+struct ChannelDepth {
+    queued: usize,
+    capacity: usize,
+}
+
+#[derive(serde::Serialize)]
+struct QueueDepth {
+    queued: u64,
+    dead_lettered: u64,
+}
+
+impl ComponentStatus {
+    fn alive(alive: bool) -> Self {
+        Self {
+            healthy: alive,
+            error: (!alive).then(|| "not running".to_string()),
+        }
+    }
+
+    fn from_result(result: Result<bool, String>, unhealthy: &str) -> Self {
+        match result {
+            Ok(true) => Self {
+                healthy: true,
+                error: None,
+            },
+            Ok(false) => Self {
+                healthy: false,
+                error: Some(unhealthy.to_string()),
+            },
+            Err(error) => Self {
+                healthy: false,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+impl ChannelDepth {
+    fn of<T>(tx: &mpsc::WeakSender<T>) -> Option<Self> {
+        let tx = tx.upgrade()?;
+
+        Some(Self {
+            queued: tx.max_capacity() - tx.capacity(),
+            capacity: tx.max_capacity(),
+        })
+    }
+}
+
+impl HealthState {
+    async fn report(&self) -> HealthReport {
+        let (storage, mtproto, bot_events) = tokio::join!(
+            probe(async { self.repo.ping().await.map(|_| true) }),
+            probe(self.monitor.is_authorized()),
+            self.event_queue_depth(),
+        );
+
+        let components = Components {
+            storage: ComponentStatus::from_result(storage, "unreachable"),
+            mtproto: ComponentStatus::from_result(mtproto, "not authorized"),
+            monitor: ComponentStatus::alive(self.monitor.is_running()),
+            bot_dispatcher: ComponentStatus::alive(self.bot.dispatcher.is_alive()),
+            monitor_events: ComponentStatus::alive(self.bot.events.is_alive()),
+        };
+
+        let healthy = [
+            &components.storage,
+            &components.mtproto,
+            &components.monitor,
+            &components.bot_dispatcher,
+            &components.monitor_events,
+        ]
+        .iter()
+        .all(|c| c.healthy);
+
+        HealthReport {
+            healthy,
+            components,
+            channels: Channels {
+                monitor_commands: ChannelDepth::of(&self.monitor_tx),
+                bot_events,
+            },
+        }
+    }
+
+    /// `None` if the repo can't be queried
+    async fn event_queue_depth(&self) -> Option<QueueDepth> {
+        let (queued, dead_lettered) = tokio::time::timeout(PROBE_TIMEOUT, async {
+            tokio::try_join!(self.repo.count_events(false), self.repo.count_events(true))
+        })
+        .await
+        .ok()?
+        .ok()?;
+
+        Some(QueueDepth {
+            queued,
+            dead_lettered,
+        })
+    }
+}
+
+async fn probe<E: std::fmt::Display>(
+    fut: impl Future<Output = Result<bool, E>>,
+) -> Result<bool, String> {
+    match tokio::time::timeout(PROBE_TIMEOUT, fut).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err("probe timed out".to_string()),
+    }
+}
+
+async fn live() -> StatusCode {
+    StatusCode::OK
+}
+
+async fn ready(State(state): State<Arc<HealthState>>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.report().await;
+
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        tracing::warn!("health check failed");
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+pub(crate) async fn serve(addr: SocketAddr, state: HealthState) -> std::io::Result<()> {
+    let app = axum::Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .with_state(Arc::new(state));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    tracing::info!(%addr, "Healthcheck server listening");
+
+    axum::serve(listener, app).await
+}
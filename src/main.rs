@@ -1,9 +1,10 @@
-use tgfeed_ai::claude::ClaudeClient;
 use tgfeed_common::command::MonitorCommand;
-use tgfeed_common::event::BotEvent;
+use tgfeed_repo::queue::EventQueue;
 use tokio::sync::mpsc;
 
 mod config;
+mod health;
+mod migrate;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -11,6 +12,11 @@ async fn main() -> anyhow::Result<()> {
 
     let config = config::Config::new();
 
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migrate::run(args.get(1).map(String::as_str), &config.repo_config).await;
+    }
+
     tracing::info!(
         server_addr = %config.server_addr,
         healthcheck_addr = %config.healthcheck_addr,
@@ -21,41 +27,68 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("failed to initialize repo");
 
-    tracing::info!(
-        database_name = %config.repo_config.database_name,
-        "Connected to database"
-    );
+    match &config.repo_config {
+        tgfeed_repo::Config::Mongodb(repo_config) => tracing::info!(
+            database_name = %repo_config.database_name,
+            "Connected to database"
+        ),
+        tgfeed_repo::Config::Sqlite(repo_config) => tracing::info!(
+            path = %repo_config.path.display(),
+            "Opened SQLite database"
+        ),
+    }
+
+    tokio::spawn(repo.clone().run_retention(config.retention_config));
 
     let (monitor_tx, monitor_rx) = mpsc::channel::<MonitorCommand>(100);
-    let (event_tx, event_rx) = mpsc::channel::<BotEvent>(100);
-
-    let monitor = match config.ai_config {
-        tgfeed_ai::Config::Claude(ai_config) => {
-            let summarizer = ClaudeClient::new(&ai_config);
-
-            tgfeed_monitor::MonitorService::new(
-                &config.monitor_config,
-                repo.clone(),
-                summarizer,
-                monitor_rx,
-                event_tx,
-            )
-            .await?
-        }
+    let events = EventQueue::new(repo.clone());
+
+    let summarizer = tgfeed_ai::AiClient::new(&config.ai_config);
+
+    let monitor = tgfeed_monitor::MonitorService::new(
+        &config.monitor_config,
+        repo.clone(),
+        summarizer,
+        monitor_rx,
+        events.clone(),
+    )
+    .await?;
+
+    let bot = tgfeed_bot::TgFeedBot::new(&config.bot_config, repo.clone(), monitor_tx.clone());
+    let api = tgfeed_api::TgFeedApi::new(&config.api_config, repo.clone(), monitor_tx.clone());
+
+    let health_state = health::HealthState {
+        repo: repo.clone(),
+        monitor: monitor.status(),
+        bot: bot.status(),
+        monitor_tx: monitor_tx.downgrade(),
     };
 
-    let bot = tgfeed_bot::TgFeedBot::new(&config.bot_config, monitor_tx.clone());
+    tokio::spawn(async move {
+        if let Err(error) = health::serve(config.healthcheck_addr, health_state).await {
+            tracing::error!(%error, "Healthcheck server failed");
+        }
+    });
+
+    tokio::spawn(async move {
+        if let Err(error) = api.run(config.server_addr).await {
+            tracing::error!(%error, "Management API server failed");
+        }
+    });
 
     tracing::info!("Starting bot and monitor...");
 
     let monitor_handle = tokio::spawn(monitor.run());
-    let bot_handle = tokio::spawn(bot.run(event_rx));
+    let bot_handle = tokio::spawn(bot.run(events.clone()));
 
     tokio::signal::ctrl_c().await?;
 
     monitor_tx.send(MonitorCommand::Shutdown).await?;
 
     monitor_handle.await??;
+
+    // Events still queued are handled after the next start
+    events.close();
     bot_handle.await??;
 
     Ok(())
@@ -0,0 +1,48 @@
+use tgfeed_repo::mongo::MongoStorage;
+
+const USAGE: &str = "Usage: tgfeed migrate [status|run]";
+
+/// `tgfeed migrate` entry point: inspect or apply schema migrations
+pub(crate) async fn run(command: Option<&str>, config: &tgfeed_repo::Config) -> anyhow::Result<()> {
+    let config = match config {
+        tgfeed_repo::Config::Mongodb(config) => config,
+        tgfeed_repo::Config::Sqlite(_) => {
+            println!("SQLite schema is created on startup, nothing to migrate");
+            return Ok(());
+        }
+    };
+
+    let storage = MongoStorage::connect(config).await?;
+
+    match command {
+        None | Some("status") => {
+            let current = storage.schema_version().await?;
+            println!(
+                "Schema version: {current} (latest {})",
+                MongoStorage::target_schema_version()
+            );
+
+            for migration in storage.migration_status().await? {
+                let state = match migration.applied_at {
+                    Some(applied_at) => format!("applied {applied_at}"),
+                    None => "pending".to_string(),
+                };
+
+                println!(
+                    "{:>4}  {:<40} {state}",
+                    migration.version, migration.description
+                );
+            }
+        }
+        Some("run") => {
+            let applied = storage.migrate().await?;
+            println!(
+                "Applied {applied} migration(s), schema version: {}",
+                storage.schema_version().await?
+            );
+        }
+        Some(_) => anyhow::bail!(USAGE),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use tgfeed_common::utils::post_url;
+
+use crate::MessageData;
+
+/// Posts of a summary request, cited by the model as `[n]` with `n` counted
+/// from 1 across all chunks
+pub(crate) struct Sources {
+    posts: Vec<(i64, i32)>,
+}
+
+impl Sources {
+    pub(crate) fn new(chunks: &[Vec<MessageData>]) -> Self {
+        let posts = chunks
+            .iter()
+            .flatten()
+            .map(|m| (m.channel_id, m.message_id))
+            .collect();
+
+        Self { posts }
+    }
+
+    /// Link of source `number`, in the format used for forwarded posts
+    fn url(&self, number: usize) -> Option<String> {
+        let (channel_id, message_id) = self.posts.get(number.checked_sub(1)?)?;
+
+        Some(post_url(*channel_id, *message_id))
+    }
+
+    /// Turn `[3]` and `[3, 7]` markers into links to the original posts.
+    /// Markers with unknown numbers are left as they are
+    pub(crate) fn link(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find('[') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            match rest
+                .find(']')
+                .and_then(|end| self.link_marker(&rest[1..end]).map(|l| (l, end)))
+            {
+                Some((linked, end)) => {
+                    result.push_str(&linked);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    result.push('[');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        result.push_str(rest);
+        result
+    }
+
+    fn link_marker(&self, marker: &str) -> Option<String> {
+        let links = marker
+            .split(',')
+            .map(|number| {
+                let number = number.trim();
+                if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+
+                let url = self.url(number.parse().ok()?)?;
+                Some(format!("<a href=\"{url}\">{number}</a>"))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(format!("[{}]", links.join(", ")))
+    }
+}
@@ -1,4 +1,31 @@
+use crate::prompt::PromptConfig;
+use crate::retry::RetryConfig;
+
 #[derive(serde::Deserialize)]
 pub struct Config {
     pub api_key: String,
+    #[serde(default = "default_model")]
+    pub model: String,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    #[serde(flatten)]
+    pub prompt: PromptConfig,
+    #[serde(flatten)]
+    pub retry: RetryConfig,
+}
+
+fn default_model() -> String {
+    "claude-sonnet-4-5-20250929".to_string()
+}
+
+fn default_max_tokens() -> u32 {
+    4096
+}
+
+fn default_base_url() -> String {
+    "https://api.anthropic.com".to_string()
 }
@@ -3,100 +3,147 @@ mod models;
 
 pub use config::Config;
 
-use crate::claude::models::{ClaudeMessage, ClaudeRequest, ClaudeResponse};
-use crate::{MessageData, Summarizer, TgfeedAiError, TgfeedAiResult};
+use crate::claude::models::{ClaudeMessage, ClaudeRequest, ClaudeResponse, Delta, StreamEvent};
+use crate::prompt::{Prompt, PromptConfig};
+use crate::retry::RetryConfig;
+use crate::sse::SseParser;
+use crate::{Completion, Progress, Reply, TgfeedAiError, TgfeedAiResult, Usage};
 
 pub struct ClaudeClient {
     client: reqwest::Client,
     api_key: String,
+    model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    messages_url: String,
+    prompt: PromptConfig,
+    retry: RetryConfig,
 }
 
 impl ClaudeClient {
     pub fn new(config: &Config) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: config.retry.client(),
             api_key: config.api_key.clone(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            temperature: config.temperature,
+            messages_url: format!("{}/v1/messages", config.base_url.trim_end_matches('/')),
+            prompt: config.prompt.clone(),
+            retry: config.retry.clone(),
         }
     }
-}
-
-impl Summarizer for ClaudeClient {
-    async fn summarize(&self, messages: Vec<MessageData>) -> TgfeedAiResult<String> {
-        if messages.is_empty() {
-            return Ok("No messages to summarize.".to_string());
-        }
 
-        let formatted: Vec<String> = messages
-            .iter()
-            .map(
-                |MessageData {
-                     channel_handle,
-                     text,
-                     date,
-                 }| {
-                    format!("@{channel_handle}\nДата и время вообщения (UTC): {date}):\n{text}",)
-                },
-            )
-            .collect();
-
-        // TODO: from config
-
-        let now = chrono::Utc::now();
-
-        let prompt = format!(
-            r#"
-            Текущие дата и время (UTC): {now}
-            Сделай сводку новотей из следущих сообщений из Telegram-каналов. Сгруппируй по теме, если возможно.
-            Форматируй, используя только HTML-тэги (и ничего другого): <b>bold</b>, <i>italic</i>, <u>underline</u>. Будь краток.
-            {}
-            "#,
-            formatted.join("\n")
-        );
-        let request = ClaudeRequest {
-            model: "claude-sonnet-4-5-20250929".to_string(),
-            max_tokens: 4096,
+    fn request(&self, prompt: Prompt, stream: bool) -> ClaudeRequest {
+        ClaudeRequest {
+            model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            system: prompt.system,
             messages: vec![ClaudeMessage {
                 role: "user".to_string(),
-                content: prompt,
+                content: prompt.user,
             }],
-        };
+            stream,
+        }
+    }
 
+    /// Send the request, failing on a non-2xx status
+    async fn open(&self, request: &ClaudeRequest) -> TgfeedAiResult<reqwest::Response> {
         let response = self
             .client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(&self.messages_url)
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
-            .json(&request)
+            .json(request)
             .send()
             .await?;
 
-        // Check status before parsing
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            tracing::error!(%status, %body, "Claude API error");
-            return Err(TgfeedAiError::Api(format!("{status}: {body}")));
+            return Err(TgfeedAiError::from_response(response).await);
         }
 
-        let response = response.json::<ClaudeResponse>().await?;
+        Ok(response)
+    }
+
+    async fn send(&self, request: &ClaudeRequest) -> TgfeedAiResult<Reply> {
+        let response = self.open(request).await?.json::<ClaudeResponse>().await?;
 
         if let Some(error) = response.error {
             return Err(TgfeedAiError::Api(error.message));
         }
 
-        let summary = response
-            .content
-            .into_iter()
-            .map(|c| c.text)
-            .collect::<String>();
+        let usage = response.usage.map_or_else(Usage::default, |u| Usage {
+            input_tokens: u.input_tokens,
+            output_tokens: u.output_tokens,
+        });
+
+        Ok(Reply {
+            text: response.content.into_iter().map(|c| c.text).collect(),
+            usage,
+        })
+    }
+}
+
+impl Completion for ClaudeClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn prompt_config(&self) -> &PromptConfig {
+        &self.prompt
+    }
+
+    async fn complete(&self, prompt: Prompt) -> TgfeedAiResult<Reply> {
+        let request = self.request(prompt, false);
 
-        tracing::info!(%summary, "generated summary");
+        self.retry.run(|| self.send(&request)).await
+    }
 
-        if summary.is_empty() {
-            Ok("No summary generated".to_string())
-        } else {
-            Ok(summary)
+    async fn complete_streaming(
+        &self,
+        prompt: Prompt,
+        progress: &Progress,
+    ) -> TgfeedAiResult<Reply> {
+        let request = self.request(prompt, true);
+
+        // Only opening the stream is retried, text may already be shown later on
+        let mut response = self.retry.run(|| self.open(&request)).await?;
+
+        let mut parser = SseParser::default();
+        let mut reply = Reply {
+            text: String::new(),
+            usage: Usage::default(),
+        };
+
+        while let Some(chunk) = response.chunk().await? {
+            for data in parser.push(&chunk) {
+                match serde_json::from_str::<StreamEvent>(&data) {
+                    Ok(StreamEvent::ContentBlockDelta {
+                        delta: Delta::TextDelta { text },
+                    }) => {
+                        reply.text.push_str(&text);
+                        // The receiver may be gone, the reply is still returned
+                        let _ = progress.send(text);
+                    }
+                    Ok(StreamEvent::MessageStart { message }) => {
+                        if let Some(usage) = message.usage {
+                            reply.usage.input_tokens = usage.input_tokens;
+                        }
+                    }
+                    Ok(StreamEvent::MessageDelta { usage }) => {
+                        reply.usage.output_tokens = usage.output_tokens;
+                    }
+                    Ok(StreamEvent::Error { error }) => {
+                        return Err(TgfeedAiError::Api(error.message));
+                    }
+                    Ok(_) => (),
+                    Err(error) => tracing::warn!(%error, %data, "unexpected Claude stream event"),
+                }
+            }
         }
+
+        Ok(reply)
     }
 }
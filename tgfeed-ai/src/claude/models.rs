@@ -2,7 +2,13 @@
 pub struct ClaudeRequest {
     pub model: String,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
     pub messages: Vec<ClaudeMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -15,9 +21,17 @@ pub struct ClaudeMessage {
 pub struct ClaudeResponse {
     pub content: Vec<Content>,
     #[serde(default)]
+    pub usage: Option<ClaudeUsage>,
+    #[serde(default)]
     pub error: Option<ApiError>,
 }
 
+#[derive(serde::Deserialize)]
+pub struct ClaudeUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
 #[derive(serde::Deserialize)]
 pub struct Content {
     pub text: String,
@@ -27,3 +41,45 @@ pub struct Content {
 pub struct ApiError {
     pub message: String,
 }
+
+/// Server-sent event of a streamed response
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    MessageStart {
+        message: StreamMessage,
+    },
+    ContentBlockDelta {
+        delta: Delta,
+    },
+    MessageDelta {
+        usage: DeltaUsage,
+    },
+    Error {
+        error: ApiError,
+    },
+    /// `ping`, `content_block_start`, `message_stop` and the like
+    #[serde(other)]
+    Other,
+}
+
+#[derive(serde::Deserialize)]
+pub struct StreamMessage {
+    #[serde(default)]
+    pub usage: Option<ClaudeUsage>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Delta {
+    TextDelta {
+        text: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeltaUsage {
+    pub output_tokens: u64,
+}
@@ -0,0 +1,53 @@
+use crate::claude::ClaudeClient;
+use crate::openai::OpenAiClient;
+use crate::prompt::{Prompt, PromptConfig};
+use crate::{Completion, Config, Progress, Reply, TgfeedAiResult};
+
+/// Summarizer backend selected through [`Config`]
+pub enum AiClient {
+    Claude(ClaudeClient),
+    OpenAi(OpenAiClient),
+}
+
+impl AiClient {
+    pub fn new(config: &Config) -> Self {
+        match config {
+            Config::Claude(config) => Self::Claude(ClaudeClient::new(config)),
+            Config::OpenAi(config) => Self::OpenAi(OpenAiClient::new(config)),
+        }
+    }
+}
+
+impl Completion for AiClient {
+    fn model(&self) -> &str {
+        match self {
+            Self::Claude(client) => client.model(),
+            Self::OpenAi(client) => client.model(),
+        }
+    }
+
+    fn prompt_config(&self) -> &PromptConfig {
+        match self {
+            Self::Claude(client) => client.prompt_config(),
+            Self::OpenAi(client) => client.prompt_config(),
+        }
+    }
+
+    async fn complete(&self, prompt: Prompt) -> TgfeedAiResult<Reply> {
+        match self {
+            Self::Claude(client) => client.complete(prompt).await,
+            Self::OpenAi(client) => client.complete(prompt).await,
+        }
+    }
+
+    async fn complete_streaming(
+        &self,
+        prompt: Prompt,
+        progress: &Progress,
+    ) -> TgfeedAiResult<Reply> {
+        match self {
+            Self::Claude(client) => client.complete_streaming(prompt, progress).await,
+            Self::OpenAi(client) => client.complete_streaming(prompt, progress).await,
+        }
+    }
+}
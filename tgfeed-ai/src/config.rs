@@ -2,4 +2,6 @@
 #[serde(rename_all = "snake_case")]
 pub enum Config {
     Claude(crate::claude::Config),
+    #[serde(rename = "openai")]
+    OpenAi(crate::openai::Config),
 }
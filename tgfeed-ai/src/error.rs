@@ -1,10 +1,64 @@
+use std::time::Duration;
+
 #[derive(Debug, thiserror::Error)]
 pub enum TgfeedAiError {
     #[error("Request error: {0}")]
     Request(#[from] reqwest::Error),
 
+    /// Rejected request that will fail the same way if repeated
     #[error("API error: {0}")]
     Api(String),
+
+    /// Rate limited or overloaded, worth retrying later
+    #[error("API temporarily unavailable: {message}")]
+    Unavailable {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
+    /// Retries took longer than the total timeout
+    #[error("AI request timed out")]
+    TimedOut,
+}
+
+impl TgfeedAiError {
+    /// Classify a non-2xx API response
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after = crate::retry::retry_after(response.headers());
+        let body = response.text().await.unwrap_or_default();
+
+        tracing::error!(%status, %body, "AI API error");
+
+        let message = format!("{status}: {body}");
+
+        // 529 is Anthropic's "overloaded"
+        if status.as_u16() == 429 || status.as_u16() == 529 || status.is_server_error() {
+            Self::Unavailable {
+                message,
+                retry_after,
+            }
+        } else {
+            Self::Api(message)
+        }
+    }
+
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Request(error) => error.is_timeout() || error.is_connect(),
+            Self::Api(_) => false,
+            Self::Unavailable { .. } => true,
+            Self::TimedOut => false,
+        }
+    }
+
+    /// Delay requested by the server, if any
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Unavailable { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
 }
 
 pub type TgfeedAiResult<T> = Result<T, TgfeedAiError>;
@@ -1,17 +1,99 @@
+mod citation;
 pub mod claude;
+mod client;
 mod config;
 mod error;
+pub mod openai;
+pub mod prompt;
+pub mod retry;
+mod sse;
+mod summarize;
 
+#[cfg(test)]
+mod tests;
+
+pub use client::AiClient;
 pub use config::Config;
 pub use error::*;
 
+use crate::prompt::{Prompt, PromptConfig};
+
 pub struct MessageData {
+    pub channel_id: i64,
     pub channel_handle: String,
+    pub message_id: i32,
     pub text: String,
     pub date: chrono::DateTime<chrono::Utc>,
 }
 
-pub trait Summarizer {
-    fn summarize(&self, messages: Vec<MessageData>)
-    -> impl Future<Output = TgfeedAiResult<String>>;
+/// Tokens billed by the backend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+}
+
+/// Generated summary or answer and what it took to produce it
+pub struct Summary {
+    pub text: String,
+    pub model: String,
+    pub usage: Usage,
+}
+
+/// Text returned by a single completion request
+pub struct Reply {
+    pub text: String,
+    pub usage: Usage,
+}
+
+/// Receives generated text as it streams in
+pub type Progress = tokio::sync::mpsc::UnboundedSender<String>;
+
+/// Shared across tasks, so requests can run off the caller's task
+pub trait Summarizer: Send + Sync {
+    /// `progress`, when given, receives the text of the final pass as it is
+    /// generated
+    fn summarize(
+        &self,
+        messages: Vec<MessageData>,
+        progress: Option<Progress>,
+    ) -> impl Future<Output = TgfeedAiResult<Summary>> + Send;
+
+    /// Answer `question` grounded in `messages`, ordered by relevance, citing
+    /// the posts used
+    fn answer(
+        &self,
+        question: &str,
+        messages: Vec<MessageData>,
+    ) -> impl Future<Output = TgfeedAiResult<Summary>> + Send;
+}
+
+/// Single request to a language model backend
+pub trait Completion: Send + Sync {
+    fn model(&self) -> &str;
+
+    fn prompt_config(&self) -> &PromptConfig;
+
+    fn complete(&self, prompt: Prompt) -> impl Future<Output = TgfeedAiResult<Reply>> + Send;
+
+    /// Like [`Completion::complete`], also sending text to `progress` as it
+    /// is generated. Backends without streaming send the whole reply at once
+    fn complete_streaming(
+        &self,
+        prompt: Prompt,
+        progress: &Progress,
+    ) -> impl Future<Output = TgfeedAiResult<Reply>> + Send {
+        async move {
+            let reply = self.complete(prompt).await?;
+            let _ = progress.send(reply.text.clone());
+            Ok(reply)
+        }
+    }
 }
@@ -0,0 +1,28 @@
+use crate::prompt::PromptConfig;
+use crate::retry::RetryConfig;
+
+#[derive(serde::Deserialize)]
+pub struct Config {
+    /// Not needed by most self-hosted servers
+    #[serde(default)]
+    pub api_key: Option<String>,
+    pub model: String,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    #[serde(flatten)]
+    pub prompt: PromptConfig,
+    #[serde(flatten)]
+    pub retry: RetryConfig,
+}
+
+fn default_max_tokens() -> u32 {
+    4096
+}
+
+fn default_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
@@ -0,0 +1,104 @@
+mod config;
+mod models;
+
+pub use config::Config;
+
+use crate::openai::models::{ChatMessage, ChatRequest, ChatResponse};
+use crate::prompt::{Prompt, PromptConfig};
+use crate::retry::RetryConfig;
+use crate::{Completion, Reply, TgfeedAiError, TgfeedAiResult, Usage};
+
+/// Client for any OpenAI-compatible chat completions API
+/// (OpenAI, gateways, llama.cpp or vLLM servers)
+pub struct OpenAiClient {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    completions_url: String,
+    prompt: PromptConfig,
+    retry: RetryConfig,
+}
+
+impl OpenAiClient {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: config.retry.client(),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            temperature: config.temperature,
+            completions_url: format!("{}/chat/completions", config.base_url.trim_end_matches('/')),
+            prompt: config.prompt.clone(),
+            retry: config.retry.clone(),
+        }
+    }
+
+    async fn send(&self, request: &ChatRequest) -> TgfeedAiResult<Reply> {
+        let mut builder = self.client.post(&self.completions_url).json(request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let response = builder.send().await?;
+
+        // Check status before parsing
+        if !response.status().is_success() {
+            return Err(TgfeedAiError::from_response(response).await);
+        }
+
+        let response = response.json::<ChatResponse>().await?;
+
+        if let Some(error) = response.error {
+            return Err(TgfeedAiError::Api(error.message));
+        }
+
+        let usage = response.usage.map_or_else(Usage::default, |u| Usage {
+            input_tokens: u.prompt_tokens,
+            output_tokens: u.completion_tokens,
+        });
+
+        Ok(Reply {
+            text: response
+                .choices
+                .into_iter()
+                .filter_map(|c| c.message.content)
+                .collect(),
+            usage,
+        })
+    }
+}
+
+impl Completion for OpenAiClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn prompt_config(&self) -> &PromptConfig {
+        &self.prompt
+    }
+
+    async fn complete(&self, prompt: Prompt) -> TgfeedAiResult<Reply> {
+        let mut chat = Vec::with_capacity(2);
+        if let Some(system) = prompt.system {
+            chat.push(ChatMessage {
+                role: "system".to_string(),
+                content: Some(system),
+            });
+        }
+        chat.push(ChatMessage {
+            role: "user".to_string(),
+            content: Some(prompt.user),
+        });
+
+        let request = ChatRequest {
+            model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            messages: chat,
+        };
+
+        self.retry.run(|| self.send(&request)).await
+    }
+}
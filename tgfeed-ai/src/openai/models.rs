@@ -0,0 +1,41 @@
+#[derive(serde::Serialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    pub messages: Vec<ChatMessage>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ChatResponse {
+    #[serde(default)]
+    pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub usage: Option<ChatUsage>,
+    #[serde(default)]
+    pub error: Option<ApiError>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ChatUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+#[derive(serde::Deserialize)]
+pub struct Choice {
+    pub message: ChatMessage,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ApiError {
+    pub message: String,
+}
@@ -0,0 +1,264 @@
+use crate::MessageData;
+
+const DEFAULT_PROMPT_TEMPLATE: &str = "\
+Current date and time (UTC): {now}
+Summarize the news from the following Telegram channel posts. Group them by topic where possible.
+Format using HTML tags only (nothing else): <b>bold</b>, <i>italic</i>, <u>underline</u>. Be concise.
+After each fact, cite the posts it comes from by their numbers in square brackets, e.g. [3] or [3, 7].
+Write the summary in {language}.
+
+{messages}";
+
+const DEFAULT_MERGE_TEMPLATE: &str = "\
+Current date and time (UTC): {now}
+Below are partial summaries of Telegram channel posts, each covering a different period of the feed.
+Merge them into a single summary. Group by topic and drop duplicates.
+Format using HTML tags only (nothing else): <b>bold</b>, <i>italic</i>, <u>underline</u>. Be concise.
+Keep the source references in square brackets, e.g. [3] or [3, 7], after the facts they support.
+Write the summary in {language}.
+
+{summaries}";
+
+const DEFAULT_ANSWER_TEMPLATE: &str = "\
+Current date and time (UTC): {now}
+Answer the question using only the following Telegram channel posts. If they don't contain the answer, say so.
+Format using HTML tags only (nothing else): <b>bold</b>, <i>italic</i>, <u>underline</u>. Be concise.
+Cite the posts each statement comes from by their numbers in square brackets, e.g. [3] or [3, 7].
+Write the answer in {language}.
+
+Question: {question}
+
+{messages}";
+
+/// Rough characters-per-token ratio, pessimistic for non-Latin scripts
+const CHARS_PER_TOKEN: usize = 3;
+
+/// Prompt settings shared by all summarizer backends.
+///
+/// Templates may reference `{now}` and `{language}`; the prompt template also
+/// `{messages}`, the merge template `{summaries}` and the answer template
+/// `{question}` and `{messages}`.
+#[derive(Clone, serde::Deserialize)]
+pub struct PromptConfig {
+    /// User message template
+    #[serde(default = "default_prompt_template")]
+    pub prompt_template: String,
+    /// Optional system prompt template
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Language of the generated summary
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Estimated tokens of messages per request; larger batches are
+    /// summarized in chunks which are then merged
+    #[serde(default = "default_chunk_token_budget")]
+    pub chunk_token_budget: usize,
+    /// Template of the final pass merging chunk summaries
+    #[serde(default = "default_merge_template")]
+    pub merge_template: String,
+    /// Template of questions answered from stored posts
+    #[serde(default = "default_answer_template")]
+    pub answer_template: String,
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        Self {
+            prompt_template: default_prompt_template(),
+            system_prompt: None,
+            language: default_language(),
+            chunk_token_budget: default_chunk_token_budget(),
+            merge_template: default_merge_template(),
+            answer_template: default_answer_template(),
+        }
+    }
+}
+
+fn default_prompt_template() -> String {
+    DEFAULT_PROMPT_TEMPLATE.to_string()
+}
+
+fn default_language() -> String {
+    "Russian".to_string()
+}
+
+fn default_chunk_token_budget() -> usize {
+    20_000
+}
+
+fn default_merge_template() -> String {
+    DEFAULT_MERGE_TEMPLATE.to_string()
+}
+
+fn default_answer_template() -> String {
+    DEFAULT_ANSWER_TEMPLATE.to_string()
+}
+
+/// Rendered prompt ready to be sent to a model
+pub struct Prompt {
+    pub system: Option<String>,
+    pub user: String,
+}
+
+impl PromptConfig {
+    /// Posts are numbered for citations starting from `first_source`
+    pub fn build(
+        &self,
+        messages: &[MessageData],
+        first_source: usize,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Prompt {
+        let messages = format_messages(messages, first_source);
+
+        self.render_with(&self.prompt_template, &[("messages", &messages)], now)
+    }
+
+    /// Prompt of the reduce pass combining partial summaries
+    pub fn build_merge(&self, summaries: &[String], now: chrono::DateTime<chrono::Utc>) -> Prompt {
+        let summaries = summaries.join("\n\n---\n\n");
+
+        self.render_with(&self.merge_template, &[("summaries", &summaries)], now)
+    }
+
+    /// Prompt answering `question` from posts numbered from 1
+    pub fn build_answer(
+        &self,
+        question: &str,
+        messages: &[MessageData],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Prompt {
+        let messages = format_messages(messages, 1);
+
+        self.render_with(
+            &self.answer_template,
+            &[("question", question), ("messages", &messages)],
+            now,
+        )
+    }
+
+    fn render_with(
+        &self,
+        template: &str,
+        content: &[(&str, &str)],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Prompt {
+        let now = now.to_string();
+
+        let mut vars = vec![("now", now.as_str()), ("language", self.language.as_str())];
+        vars.extend_from_slice(content);
+
+        Prompt {
+            system: self.system_prompt.as_deref().map(|t| render(t, &vars)),
+            user: render(template, &vars),
+        }
+    }
+
+    /// Split messages into chronological chunks fitting the token budget.
+    ///
+    /// A message larger than the budget gets a chunk of its own.
+    pub fn chunk(&self, mut messages: Vec<MessageData>) -> Vec<Vec<MessageData>> {
+        messages.sort_by_key(|m| m.date);
+
+        let mut chunks = Vec::new();
+        let mut chunk = Vec::new();
+        let mut chunk_tokens = 0;
+
+        for message in messages {
+            let tokens = estimate_tokens(&message);
+
+            if !chunk.is_empty() && chunk_tokens + tokens > self.chunk_token_budget {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_tokens = 0;
+            }
+
+            chunk_tokens += tokens;
+            chunk.push(message);
+        }
+
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+
+        chunks
+    }
+
+    /// Leading messages fitting the token budget, at least one, in
+    /// chronological order
+    pub fn fit(&self, messages: Vec<MessageData>) -> Vec<MessageData> {
+        let mut tokens = 0;
+
+        let mut fitting = messages
+            .into_iter()
+            .enumerate()
+            .take_while(|(i, message)| {
+                tokens += estimate_tokens(message);
+                *i == 0 || tokens <= self.chunk_token_budget
+            })
+            .map(|(_, message)| message)
+            .collect::<Vec<_>>();
+
+        fitting.sort_by_key(|m| m.date);
+        fitting
+    }
+}
+
+/// Estimated tokens of a message as formatted in the prompt
+pub(crate) fn estimate_tokens(message: &MessageData) -> usize {
+    // source number, handle and date header
+    const HEADER_CHARS: usize = 64;
+
+    (message.channel_handle.chars().count() + message.text.chars().count() + HEADER_CHARS)
+        .div_ceil(CHARS_PER_TOKEN)
+}
+
+pub(crate) fn format_messages(messages: &[MessageData], first_source: usize) -> String {
+    messages
+        .iter()
+        .zip(first_source..)
+        .map(
+            |(
+                MessageData {
+                    channel_handle,
+                    text,
+                    date,
+                    ..
+                },
+                number,
+            )| format!("[{number}] @{channel_handle}\nPosted at (UTC): {date}\n{text}"),
+        )
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Substitute `{name}` placeholders in a single pass, so values are never
+/// themselves expanded. Unknown placeholders are kept as is.
+pub(crate) fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| (*value, end))
+        });
+
+        match value {
+            Some((value, end)) => {
+                result.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use crate::{TgfeedAiError, TgfeedAiResult};
+
+/// Timeout and retry policy of API requests
+#[derive(Clone, serde::Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt of a retriable failure
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled after every attempt
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Timeout of a single request
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Time limit of a request including all of its retries
+    #[serde(default = "default_total_timeout_secs")]
+    pub total_timeout_secs: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            timeout_secs: default_timeout_secs(),
+            total_timeout_secs: default_total_timeout_secs(),
+        }
+    }
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30_000
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+fn default_total_timeout_secs() -> u64 {
+    90
+}
+
+impl RetryConfig {
+    pub(crate) fn client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .build()
+            .expect("failed to build HTTP client")
+    }
+
+    /// Exponential backoff before retry number `attempt` (starting at 0)
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(2u64.saturating_pow(attempt));
+
+        Duration::from_millis(delay.min(self.max_backoff_ms))
+    }
+
+    /// Run `request` until it succeeds, fails permanently or runs out of
+    /// retries or time, honoring the server's `retry-after` when present
+    pub(crate) async fn run<T, F, Fut>(&self, mut request: F) -> TgfeedAiResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = TgfeedAiResult<T>>,
+    {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(self.total_timeout_secs);
+        let mut attempt = 0;
+
+        loop {
+            let result = tokio::time::timeout_at(deadline, request())
+                .await
+                .unwrap_or(Err(TgfeedAiError::TimedOut));
+
+            match result {
+                Err(error) if error.is_retriable() && attempt < self.max_retries => {
+                    let delay = error.retry_after().unwrap_or_else(|| self.backoff(attempt));
+
+                    if tokio::time::Instant::now() + delay >= deadline {
+                        tracing::warn!(%error, attempt, "AI request failed, no time left to retry");
+                        return Err(error);
+                    }
+
+                    tracing::warn!(%error, attempt, ?delay, "AI request failed, retrying");
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Parse a `retry-after` header given in seconds
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
@@ -0,0 +1,142 @@
+use crate::citation::Sources;
+use crate::prompt::Prompt;
+use crate::{Completion, MessageData, Progress, Reply, Summarizer, Summary, TgfeedAiResult, Usage};
+
+/// Map-reduce summarization on top of any completion backend: batches over
+/// the token budget are summarized chunk by chunk, then merged in a final pass.
+/// Answers use the most relevant posts fitting the budget in a single pass
+impl<C: Completion> Summarizer for C {
+    async fn summarize(
+        &self,
+        messages: Vec<MessageData>,
+        progress: Option<Progress>,
+    ) -> TgfeedAiResult<Summary> {
+        let mut usage = Usage::default();
+
+        let text = if messages.is_empty() {
+            "No messages to summarize.".to_string()
+        } else {
+            let text = summarize_chunks(self, messages, progress.as_ref(), &mut usage).await?;
+
+            tracing::info!(summary = %text, ?usage, "generated summary");
+
+            if text.is_empty() {
+                "No summary generated".to_string()
+            } else {
+                text
+            }
+        };
+
+        Ok(Summary {
+            text,
+            model: self.model().to_string(),
+            usage,
+        })
+    }
+
+    async fn answer(&self, question: &str, messages: Vec<MessageData>) -> TgfeedAiResult<Summary> {
+        if messages.is_empty() {
+            return Ok(Summary {
+                text: "No messages to answer from.".to_string(),
+                model: self.model().to_string(),
+                usage: Usage::default(),
+            });
+        }
+
+        let config = self.prompt_config();
+        let messages = config.fit(messages);
+        let sources = Sources::new(std::slice::from_ref(&messages));
+
+        let reply = self
+            .complete(config.build_answer(question, &messages, chrono::Utc::now()))
+            .await?;
+
+        tracing::info!(answer = %reply.text, usage = ?reply.usage, "generated answer");
+
+        Ok(Summary {
+            text: sources.link(&reply.text),
+            model: self.model().to_string(),
+            usage: reply.usage,
+        })
+    }
+}
+
+/// Summary with source markers linked to the original posts
+async fn summarize_chunks<C: Completion>(
+    backend: &C,
+    messages: Vec<MessageData>,
+    progress: Option<&Progress>,
+    usage: &mut Usage,
+) -> TgfeedAiResult<String> {
+    let chunks = backend.prompt_config().chunk(messages);
+    let sources = Sources::new(&chunks);
+
+    let text = generate(backend, &chunks, progress, usage).await?;
+
+    Ok(sources.link(&text))
+}
+
+async fn generate<C: Completion>(
+    backend: &C,
+    chunks: &[Vec<MessageData>],
+    progress: Option<&Progress>,
+    usage: &mut Usage,
+) -> TgfeedAiResult<String> {
+    let config = backend.prompt_config();
+    let now = chrono::Utc::now();
+
+    if let [chunk] = chunks {
+        let reply = complete_final(backend, config.build(chunk, 1, now), progress).await?;
+        *usage += reply.usage;
+
+        return Ok(reply.text);
+    }
+
+    tracing::info!(chunks = chunks.len(), "summarizing in chunks");
+
+    let mut partials = Vec::with_capacity(chunks.len());
+    let mut first_source = 1;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let reply = backend
+            .complete(config.build(chunk, first_source, now))
+            .await?;
+        *usage += reply.usage;
+        first_source += chunk.len();
+
+        tracing::info!(chunk = i + 1, posts = chunk.len(), "summarized chunk");
+
+        if !reply.text.is_empty() {
+            partials.push(reply.text);
+        }
+    }
+
+    match partials.len() {
+        0 | 1 => {
+            let text = partials.pop().unwrap_or_default();
+            if let Some(progress) = progress {
+                let _ = progress.send(text.clone());
+            }
+
+            Ok(text)
+        }
+        _ => {
+            let reply =
+                complete_final(backend, config.build_merge(&partials, now), progress).await?;
+            *usage += reply.usage;
+
+            Ok(reply.text)
+        }
+    }
+}
+
+/// Final pass, streamed when someone is watching
+async fn complete_final<C: Completion>(
+    backend: &C,
+    prompt: Prompt,
+    progress: Option<&Progress>,
+) -> TgfeedAiResult<Reply> {
+    match progress {
+        Some(progress) => backend.complete_streaming(prompt, progress).await,
+        None => backend.complete(prompt).await,
+    }
+}
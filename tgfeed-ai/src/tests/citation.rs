@@ -0,0 +1,40 @@
+use chrono::TimeZone;
+
+use crate::MessageData;
+use crate::citation::Sources;
+
+fn sources() -> Sources {
+    let message = |channel_id, message_id| MessageData {
+        channel_id,
+        channel_handle: "channel".to_string(),
+        message_id,
+        text: String::new(),
+        date: chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+    };
+
+    Sources::new(&[vec![message(10, 1), message(10, 2)], vec![message(20, 5)]])
+}
+
+#[test]
+fn test_link_single_marker() {
+    assert_eq!(
+        sources().link("Rates went up [3]."),
+        "Rates went up [<a href=\"https://t.me/c/20/5\">3</a>]."
+    );
+}
+
+#[test]
+fn test_link_marker_list() {
+    assert_eq!(
+        sources().link("Storm [1, 2]"),
+        "Storm [<a href=\"https://t.me/c/10/1\">1</a>, <a href=\"https://t.me/c/10/2\">2</a>]"
+    );
+}
+
+#[test]
+fn test_link_keeps_unknown_markers() {
+    assert_eq!(
+        sources().link("[0] [4] [1, 9] [note] [ ] [1"),
+        "[0] [4] [1, 9] [note] [ ] [1"
+    );
+}
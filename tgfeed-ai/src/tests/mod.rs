@@ -0,0 +1,5 @@
+mod citation;
+mod prompt;
+mod retry;
+mod sse;
+mod summarize;
@@ -0,0 +1,191 @@
+use chrono::TimeZone;
+
+use crate::MessageData;
+use crate::prompt::{PromptConfig, estimate_tokens, render};
+
+fn message(channel_handle: &str, text: &str) -> MessageData {
+    MessageData {
+        channel_id: 1,
+        channel_handle: channel_handle.to_string(),
+        message_id: 1,
+        text: text.to_string(),
+        date: chrono::Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+    }
+}
+
+#[test]
+fn test_render_substitutes_known_placeholders() {
+    let rendered = render("{a} and {b}", &[("a", "1"), ("b", "2")]);
+    assert_eq!(rendered, "1 and 2");
+}
+
+#[test]
+fn test_render_keeps_unknown_placeholders() {
+    let rendered = render("{a} {unknown} {", &[("a", "1")]);
+    assert_eq!(rendered, "1 {unknown} {");
+}
+
+#[test]
+fn test_render_does_not_expand_values() {
+    let rendered = render("{messages} {now}", &[
+        ("messages", "{now}"),
+        ("now", "today"),
+    ]);
+    assert_eq!(rendered, "{now} today");
+}
+
+#[test]
+fn test_build_default_prompt() {
+    let config = PromptConfig::default();
+    let now = chrono::Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap();
+
+    let prompt = config.build(&[message("channel", "Hello world")], 1, now);
+
+    assert!(prompt.system.is_none());
+    assert!(prompt.user.contains("2025-01-03 00:00:00 UTC"));
+    assert!(prompt.user.contains("Write the summary in Russian."));
+    assert!(
+        prompt
+            .user
+            .contains("[1] @channel\nPosted at (UTC): 2025-01-02 03:04:05 UTC\nHello world")
+    );
+}
+
+#[test]
+fn test_build_custom_templates() {
+    let config = PromptConfig {
+        prompt_template: "{messages}".to_string(),
+        system_prompt: Some("Answer in {language}".to_string()),
+        language: "English".to_string(),
+        ..Default::default()
+    };
+
+    let prompt = config.build(
+        &[message("a", "one"), message("b", "two")],
+        5,
+        chrono::Utc::now(),
+    );
+
+    assert_eq!(prompt.system.as_deref(), Some("Answer in English"));
+    assert_eq!(prompt.user.matches("Posted at (UTC)").count(), 2);
+    assert!(prompt.user.starts_with("[5] @a\n"));
+    assert!(prompt.user.contains("[6] @b\n"));
+}
+
+#[test]
+fn test_chunk_within_budget_single_chunk() {
+    let config = PromptConfig::default();
+
+    let chunks = config.chunk(vec![message("a", "one"), message("b", "two")]);
+
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].len(), 2);
+}
+
+#[test]
+fn test_chunk_splits_chronologically() {
+    let config = PromptConfig {
+        chunk_token_budget: 100,
+        ..Default::default()
+    };
+
+    let messages = (0..10)
+        .rev()
+        .map(|i| MessageData {
+            channel_id: 1,
+            channel_handle: "channel".to_string(),
+            message_id: i as i32,
+            text: "x".repeat(100),
+            date: chrono::Utc.with_ymd_and_hms(2025, 1, 1, i, 0, 0).unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    let chunks = config.chunk(messages);
+
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.iter().map(Vec::len).sum::<usize>(), 10);
+    for chunk in &chunks {
+        assert!(chunk.iter().map(estimate_tokens).sum::<usize>() <= 100);
+    }
+
+    let dates = chunks.iter().flatten().map(|m| m.date).collect::<Vec<_>>();
+    assert!(dates.is_sorted());
+}
+
+#[test]
+fn test_chunk_oversized_message_alone() {
+    let config = PromptConfig {
+        chunk_token_budget: 10,
+        ..Default::default()
+    };
+
+    let chunks = config.chunk(vec![message("a", &"x".repeat(1000)), message("b", "two")]);
+
+    assert_eq!(chunks.len(), 2);
+}
+
+#[test]
+fn test_build_merge() {
+    let config = PromptConfig::default();
+
+    let prompt = config.build_merge(
+        &["first part".to_string(), "second part".to_string()],
+        chrono::Utc::now(),
+    );
+
+    assert!(prompt.user.contains("first part\n\n---\n\nsecond part"));
+    assert!(prompt.user.contains("Write the summary in Russian."));
+}
+
+#[test]
+fn test_build_answer() {
+    let config = PromptConfig::default();
+
+    let prompt = config.build_answer(
+        "What happened?",
+        &[message("a", "one"), message("b", "two")],
+        chrono::Utc::now(),
+    );
+
+    assert!(prompt.user.contains("Question: What happened?"));
+    assert!(prompt.user.contains("[1] @a\n"));
+    assert!(prompt.user.contains("[2] @b\n"));
+}
+
+#[test]
+fn test_fit_keeps_leading_messages_chronologically() {
+    let config = PromptConfig {
+        chunk_token_budget: 120,
+        ..Default::default()
+    };
+
+    let messages = (0..10)
+        .map(|i| MessageData {
+            channel_id: 1,
+            channel_handle: "channel".to_string(),
+            message_id: i as i32,
+            text: "x".repeat(100),
+            date: chrono::Utc
+                .with_ymd_and_hms(2025, 1, 1, 10 - i, 0, 0)
+                .unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    let fitting = config.fit(messages);
+
+    let ids = fitting.iter().map(|m| m.message_id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 0]);
+}
+
+#[test]
+fn test_fit_keeps_oversized_first_message() {
+    let config = PromptConfig {
+        chunk_token_budget: 10,
+        ..Default::default()
+    };
+
+    let fitting = config.fit(vec![message("a", &"x".repeat(1000)), message("b", "two")]);
+
+    assert_eq!(fitting.len(), 1);
+    assert_eq!(fitting[0].channel_handle, "a");
+}
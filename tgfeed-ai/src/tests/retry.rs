@@ -0,0 +1,127 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+use crate::TgfeedAiError;
+use crate::retry::{RetryConfig, retry_after};
+
+fn config(max_retries: u32) -> RetryConfig {
+    RetryConfig {
+        max_retries,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 4,
+        ..Default::default()
+    }
+}
+
+fn unavailable() -> TgfeedAiError {
+    TgfeedAiError::Unavailable {
+        message: "529: overloaded".to_string(),
+        retry_after: None,
+    }
+}
+
+#[test]
+fn test_backoff_doubles_up_to_max() {
+    let config = config(10);
+
+    let delays = (0..5).map(|a| config.backoff(a)).collect::<Vec<_>>();
+    let expected = [1, 2, 4, 4, 4].map(Duration::from_millis);
+    assert_eq!(delays, expected);
+    assert_eq!(config.backoff(u32::MAX), Duration::from_millis(4));
+}
+
+#[test]
+fn test_retry_after_header() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    assert_eq!(retry_after(&headers), None);
+}
+
+#[tokio::test]
+async fn test_retries_transient_errors() {
+    let attempts = Cell::new(0);
+
+    let result = config(3)
+        .run(|| async {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 3 {
+                Err(unavailable())
+            } else {
+                Ok("summary")
+            }
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), "summary");
+    assert_eq!(attempts.get(), 3);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() {
+    let attempts = Cell::new(0);
+
+    let result: Result<(), _> = config(2)
+        .run(|| async {
+            attempts.set(attempts.get() + 1);
+            Err(unavailable())
+        })
+        .await;
+
+    assert!(matches!(result, Err(TgfeedAiError::Unavailable { .. })));
+    assert_eq!(attempts.get(), 3);
+}
+
+#[tokio::test]
+async fn test_permanent_errors_not_retried() {
+    let attempts = Cell::new(0);
+
+    let result: Result<(), _> = config(3)
+        .run(|| async {
+            attempts.set(attempts.get() + 1);
+            Err(TgfeedAiError::Api("400: bad request".to_string()))
+        })
+        .await;
+
+    assert!(!result.unwrap_err().is_retriable());
+    assert_eq!(attempts.get(), 1);
+}
+
+#[tokio::test]
+async fn test_no_retry_past_total_timeout() {
+    let attempts = Cell::new(0);
+
+    let result: Result<(), _> = RetryConfig {
+        total_timeout_secs: 0,
+        ..config(10)
+    }
+    .run(|| async {
+        attempts.set(attempts.get() + 1);
+        Err(unavailable())
+    })
+    .await;
+
+    assert!(matches!(result, Err(TgfeedAiError::Unavailable { .. })));
+    assert_eq!(attempts.get(), 1);
+}
+
+#[tokio::test]
+async fn test_stuck_request_times_out() {
+    let result: Result<(), _> = RetryConfig {
+        total_timeout_secs: 0,
+        ..config(3)
+    }
+    .run(std::future::pending)
+    .await;
+
+    assert!(matches!(result, Err(TgfeedAiError::TimedOut)));
+}
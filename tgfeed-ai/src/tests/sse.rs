@@ -0,0 +1,27 @@
+use crate::sse::SseParser;
+
+#[test]
+fn test_sse_events_split_across_chunks() {
+    let mut parser = SseParser::default();
+
+    assert!(
+        parser
+            .push(b"event: content_block_delta\ndata: {\"a\":")
+            .is_empty()
+    );
+    assert_eq!(parser.push(b"1}\n\n: comment\n"), vec![r#"{"a":1}"#]);
+    assert_eq!(
+        parser.push(b"data: first\r\ndata:second\r\n\r\ndata: third\n\n"),
+        vec!["first\nsecond", "third"]
+    );
+}
+
+#[test]
+fn test_sse_multibyte_character_split() {
+    let mut parser = SseParser::default();
+    let event = "data: привет\n\n".as_bytes();
+
+    // Split inside the two-byte "п"
+    assert!(parser.push(&event[..7]).is_empty());
+    assert_eq!(parser.push(&event[7..]), vec!["привет"]);
+}
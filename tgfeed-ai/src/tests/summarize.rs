@@ -0,0 +1,158 @@
+use std::sync::Mutex;
+
+use chrono::TimeZone;
+
+use crate::prompt::{Prompt, PromptConfig};
+use crate::{Completion, MessageData, Reply, Summarizer, TgfeedAiResult, Usage};
+
+/// Completion backend answering with the request number
+struct FakeCompletion {
+    prompt: PromptConfig,
+    requests: Mutex<Vec<String>>,
+}
+
+impl FakeCompletion {
+    fn new(chunk_token_budget: usize) -> Self {
+        Self {
+            prompt: PromptConfig {
+                prompt_template: "posts: {messages}".to_string(),
+                merge_template: "merge: {summaries}".to_string(),
+                chunk_token_budget,
+                ..Default::default()
+            },
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Completion for FakeCompletion {
+    fn model(&self) -> &str {
+        "fake"
+    }
+
+    fn prompt_config(&self) -> &PromptConfig {
+        &self.prompt
+    }
+
+    async fn complete(&self, prompt: Prompt) -> TgfeedAiResult<Reply> {
+        let mut requests = self.requests.lock().unwrap();
+        requests.push(prompt.user);
+        Ok(Reply {
+            text: format!("summary {}", requests.len()),
+            usage: Usage {
+                input_tokens: 10,
+                output_tokens: 1,
+            },
+        })
+    }
+}
+
+fn messages(count: u32) -> Vec<MessageData> {
+    (0..count)
+        .map(|i| MessageData {
+            channel_id: 1,
+            channel_handle: "channel".to_string(),
+            message_id: i as i32,
+            text: "x".repeat(100),
+            date: chrono::Utc.with_ymd_and_hms(2025, 1, 1, i, 0, 0).unwrap(),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_summarize_single_pass() {
+    let backend = FakeCompletion::new(100_000);
+
+    let summary = backend.summarize(messages(5), None).await.unwrap();
+
+    assert_eq!(summary.text, "summary 1");
+    assert_eq!(summary.model, "fake");
+    assert_eq!(backend.requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_summarize_map_reduce() {
+    let backend = FakeCompletion::new(100);
+
+    let summary = backend.summarize(messages(10), None).await.unwrap();
+
+    let requests = backend.requests.lock().unwrap();
+    assert!(requests.len() > 2);
+    assert_eq!(summary.text, format!("summary {}", requests.len()));
+    assert_eq!(summary.usage, Usage {
+        input_tokens: 10 * requests.len() as u64,
+        output_tokens: requests.len() as u64,
+    });
+
+    let merge = requests.last().unwrap();
+    assert!(merge.starts_with("merge: summary 1\n\n---\n\nsummary 2"));
+    assert!(
+        requests[..requests.len() - 1]
+            .iter()
+            .all(|r| r.starts_with("posts: "))
+    );
+}
+
+#[tokio::test]
+async fn test_summarize_empty() {
+    let backend = FakeCompletion::new(100);
+
+    let summary = backend.summarize(Vec::new(), None).await.unwrap();
+
+    assert_eq!(summary.text, "No messages to summarize.");
+    assert_eq!(summary.usage, Usage::default());
+    assert!(backend.requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_summarize_streams_final_pass_only() {
+    let backend = FakeCompletion::new(100);
+    let (progress, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let summary = backend
+        .summarize(messages(10), Some(progress))
+        .await
+        .unwrap();
+
+    let mut streamed = Vec::new();
+    while let Ok(text) = rx.try_recv() {
+        streamed.push(text);
+    }
+
+    assert_eq!(streamed, vec![summary.text]);
+}
+
+#[tokio::test]
+async fn test_summarize_numbers_sources_across_chunks() {
+    let backend = FakeCompletion::new(100);
+
+    backend.summarize(messages(10), None).await.unwrap();
+
+    let requests = backend.requests.lock().unwrap();
+    let posts = requests[..requests.len() - 1].join("\n");
+    for number in 1..=10 {
+        assert_eq!(posts.matches(&format!("[{number}] @channel")).count(), 1);
+    }
+}
+
+#[tokio::test]
+async fn test_answer_single_request() {
+    let backend = FakeCompletion::new(100_000);
+
+    let answer = backend.answer("why?", messages(5)).await.unwrap();
+
+    let requests = backend.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(answer.text, "summary 1");
+    assert_eq!(answer.usage.input_tokens, 10);
+}
+
+#[tokio::test]
+async fn test_answer_empty() {
+    let backend = FakeCompletion::new(100);
+
+    let answer = backend.answer("why?", Vec::new()).await.unwrap();
+
+    assert_eq!(answer.text, "No messages to answer from.");
+    assert!(backend.requests.lock().unwrap().is_empty());
+}
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+#[derive(serde::Deserialize)]
+pub struct Config {
+    /// Bearer token required on every request, must not be empty
+    #[serde(deserialize_with = "non_empty_token")]
+    pub token: String,
+}
+
+/// An empty token would match a bare `Authorization: Bearer ` header
+pub(crate) fn non_empty_token<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let token = String::deserialize(deserializer)?;
+
+    if token.trim().is_empty() {
+        return Err(serde::de::Error::custom("API token must not be empty"));
+    }
+
+    Ok(token)
+}
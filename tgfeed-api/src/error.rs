@@ -0,0 +1,50 @@
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Channel handle is empty")]
+    EmptyHandle,
+
+    #[error("{0}")]
+    Monitor(String),
+
+    #[error("Monitor is unavailable")]
+    MonitorUnavailable,
+
+    #[error("Repository error: {0}")]
+    Repo(#[from] tgfeed_repo::TgFeedRepoError),
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+#[derive(serde::Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::EmptyHandle => StatusCode::BAD_REQUEST,
+            ApiError::Monitor(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::MonitorUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Repo(error) => {
+                tracing::error!(%error, "repository request failed");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        let error = match &self {
+            ApiError::Repo(_) => "Internal server error".to_string(),
+            other => other.to_string(),
+        };
+
+        (status, Json(ErrorResponse { error })).into_response()
+    }
+}
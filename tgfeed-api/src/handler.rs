@@ -0,0 +1,141 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use tgfeed_common::command::MonitorCommand;
+
+use crate::models::{
+    DeliveryResponse, DroppedAdsResponse, HistoryQuery, StoredSummaryResponse, SubscribeRequest,
+    SummaryResponse, UpdateUserRequest, UserResponse,
+};
+use crate::{ApiError, ApiResult, TgFeedApi};
+
+pub(crate) fn normalize_handle(channel_handle: &str) -> ApiResult<String> {
+    let channel_handle = channel_handle.trim().trim_start_matches('@');
+
+    if channel_handle.is_empty() {
+        Err(ApiError::EmptyHandle)
+    } else {
+        Ok(channel_handle.to_string())
+    }
+}
+
+pub(crate) async fn list_subscriptions(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+) -> ApiResult<Json<Vec<String>>> {
+    let subscriptions = this
+        .request(|response| MonitorCommand::ListSubscriptions { user_id, response })
+        .await?;
+
+    Ok(Json(subscriptions))
+}
+
+pub(crate) async fn subscribe(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+    Json(request): Json<SubscribeRequest>,
+) -> ApiResult<StatusCode> {
+    let channel_handle = normalize_handle(&request.channel_handle)?;
+
+    this.request(|response| MonitorCommand::Subscribe {
+        user_id,
+        channel_handle,
+        response,
+    })
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) async fn unsubscribe(
+    State(this): State<TgFeedApi>,
+    Path((user_id, channel_handle)): Path<(i64, String)>,
+) -> ApiResult<StatusCode> {
+    let channel_handle = normalize_handle(&channel_handle)?;
+
+    this.request(|response| MonitorCommand::Unsubscribe {
+        user_id,
+        channel_handle,
+        response,
+    })
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) async fn summarize(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+) -> ApiResult<Json<SummaryResponse>> {
+    let summary = this
+        .request(|response| MonitorCommand::Summarize {
+            user_id,
+            progress: None,
+            response,
+        })
+        .await?;
+
+    Ok(Json(SummaryResponse { summary }))
+}
+
+pub(crate) async fn list_summaries(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Json<Vec<StoredSummaryResponse>>> {
+    let limit = query.limit.clamp(1, 100);
+    let summaries = this.repo.get_user_summaries(user_id, limit).await?;
+
+    Ok(Json(
+        summaries
+            .into_iter()
+            .map(StoredSummaryResponse::from)
+            .collect(),
+    ))
+}
+
+pub(crate) async fn list_deliveries(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Json<Vec<DeliveryResponse>>> {
+    let limit = query.limit.clamp(1, 100);
+    let deliveries = this.repo.get_user_deliveries(user_id, limit).await?;
+
+    Ok(Json(
+        deliveries.into_iter().map(DeliveryResponse::from).collect(),
+    ))
+}
+
+pub(crate) async fn list_users(
+    State(this): State<TgFeedApi>,
+) -> ApiResult<Json<Vec<UserResponse>>> {
+    let users = this.repo.get_users().await?;
+
+    Ok(Json(users.into_iter().map(UserResponse::from).collect()))
+}
+
+pub(crate) async fn update_user(
+    State(this): State<TgFeedApi>,
+    Path(user_id): Path<i64>,
+    Json(request): Json<UpdateUserRequest>,
+) -> ApiResult<StatusCode> {
+    this.repo.set_user_allowed(user_id, request.allowed).await?;
+
+    tracing::info!(%user_id, allowed = request.allowed, "user access updated");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Posts dropped as ads since startup, for auditing false positives
+pub(crate) async fn dropped_ads(
+    State(this): State<TgFeedApi>,
+) -> ApiResult<Json<Vec<DroppedAdsResponse>>> {
+    let dropped = this
+        .request(|response| MonitorCommand::DroppedAds { response })
+        .await?;
+
+    Ok(Json(
+        dropped.into_iter().map(DroppedAdsResponse::from).collect(),
+    ))
+}
@@ -0,0 +1,95 @@
+mod auth;
+mod config;
+mod error;
+mod handler;
+mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post, put};
+pub use config::Config;
+pub use error::*;
+use tgfeed_common::command::MonitorCommand;
+use tokio::sync::{mpsc, oneshot};
+
+/// HTTP management API mirroring the bot commands
+#[derive(Clone)]
+pub struct TgFeedApi {
+    token: Arc<str>,
+    repo: tgfeed_repo::Repo,
+    monitor_tx: mpsc::Sender<MonitorCommand>,
+}
+
+impl TgFeedApi {
+    pub fn new(
+        config: &Config,
+        repo: tgfeed_repo::Repo,
+        monitor_tx: mpsc::Sender<MonitorCommand>,
+    ) -> Self {
+        Self {
+            token: Arc::from(config.token.as_str()),
+            repo,
+            monitor_tx,
+        }
+    }
+
+    pub async fn run(self, addr: SocketAddr) -> std::io::Result<()> {
+        let app = axum::Router::new()
+            .route(
+                "/users/{user_id}/subscriptions",
+                get(handler::list_subscriptions).post(handler::subscribe),
+            )
+            .route(
+                "/users/{user_id}/subscriptions/{channel_handle}",
+                delete(handler::unsubscribe),
+            )
+            .route("/users/{user_id}/summarize", post(handler::summarize))
+            .route("/users/{user_id}/summaries", get(handler::list_summaries))
+            .route("/users/{user_id}/deliveries", get(handler::list_deliveries))
+            .route("/users", get(handler::list_users))
+            .route("/users/{user_id}", put(handler::update_user))
+            .route("/stats/ads", get(handler::dropped_ads))
+            .route_layer(axum::middleware::from_fn_with_state(
+                self.clone(),
+                auth::require_token,
+            ))
+            .with_state(self);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+
+        tracing::info!(%addr, "Management API listening");
+
+        axum::serve(listener, app).await
+    }
+
+    /// Send a command to the monitor and wait for its response.
+    ///
+    /// The round trip runs in a separate task so that a client disconnect
+    /// does not drop the receiver the monitor is about to respond to.
+    async fn request<T: Send + 'static>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<Result<T, String>>) -> MonitorCommand,
+    ) -> ApiResult<T> {
+        let (tx, rx) = oneshot::channel();
+        let command = command(tx);
+        let monitor_tx = self.monitor_tx.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = monitor_tx.send(command).await {
+                tracing::error!(%error, "communication with monitor failed");
+                return Err(ApiError::MonitorUnavailable);
+            }
+
+            match rx.await {
+                Ok(result) => result.map_err(ApiError::Monitor),
+                Err(_) => Err(ApiError::MonitorUnavailable),
+            }
+        })
+        .await
+        .map_err(|_| ApiError::MonitorUnavailable)?
+    }
+}
@@ -0,0 +1,110 @@
+#[derive(serde::Deserialize)]
+pub struct SubscribeRequest {
+    pub channel_handle: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct SummaryResponse {
+    pub summary: String,
+}
+
+#[derive(serde::Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_history_limit")]
+    pub limit: i64,
+}
+
+fn default_history_limit() -> i64 {
+    10
+}
+
+#[derive(serde::Serialize)]
+pub struct StoredSummaryResponse {
+    pub since: chrono::DateTime<chrono::Utc>,
+    pub until: chrono::DateTime<chrono::Utc>,
+    pub channels: Vec<String>,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub html: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<tgfeed_repo::models::StoredSummary> for StoredSummaryResponse {
+    fn from(summary: tgfeed_repo::models::StoredSummary) -> Self {
+        Self {
+            since: summary.since,
+            until: summary.until,
+            channels: summary.channels,
+            model: summary.model,
+            input_tokens: summary.input_tokens,
+            output_tokens: summary.output_tokens,
+            html: summary.html,
+            created_at: summary.created_at,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct DeliveryResponse {
+    pub channel_id: i64,
+    pub message_id: i32,
+    pub chat_message_id: Option<i32>,
+    pub status: tgfeed_repo::models::DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub attempted_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<tgfeed_repo::models::Delivery> for DeliveryResponse {
+    fn from(delivery: tgfeed_repo::models::Delivery) -> Self {
+        Self {
+            channel_id: delivery.channel_id,
+            message_id: delivery.message_id,
+            chat_message_id: delivery.chat_message_id,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_error: delivery.last_error,
+            attempted_at: delivery.attempted_at,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdateUserRequest {
+    pub allowed: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct UserResponse {
+    pub telegram_id: i64,
+    pub allowed: bool,
+    pub active: bool,
+}
+
+impl From<tgfeed_repo::models::User> for UserResponse {
+    fn from(user: tgfeed_repo::models::User) -> Self {
+        Self {
+            telegram_id: user.telegram_id,
+            allowed: user.allowed,
+            active: user.active,
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct DroppedAdsResponse {
+    pub channel: String,
+    pub rule: String,
+    pub count: u64,
+}
+
+impl From<tgfeed_common::command::DroppedAds> for DroppedAdsResponse {
+    fn from(dropped: tgfeed_common::command::DroppedAds) -> Self {
+        Self {
+            channel: dropped.channel,
+            rule: dropped.rule,
+            count: dropped.count,
+        }
+    }
+}
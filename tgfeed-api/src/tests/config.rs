@@ -0,0 +1,15 @@
+use serde::de::IntoDeserializer;
+use serde::de::value::Error;
+
+use crate::config::non_empty_token;
+
+fn token(value: &str) -> Result<String, Error> {
+    non_empty_token(value.into_deserializer())
+}
+
+#[test]
+fn test_empty_token_rejected() {
+    assert!(token("").is_err());
+    assert!(token("  ").is_err());
+    assert_eq!(token("secret").unwrap(), "secret");
+}
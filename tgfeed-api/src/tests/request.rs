@@ -0,0 +1,27 @@
+use crate::ApiError;
+use crate::auth::constant_time_eq;
+use crate::handler::normalize_handle;
+
+#[test]
+fn test_constant_time_eq() {
+    assert!(constant_time_eq(b"secret", b"secret"));
+    assert!(!constant_time_eq(b"secret", b"secreT"));
+    assert!(!constant_time_eq(b"secret", b"secret2"));
+    assert!(!constant_time_eq(b"", b"secret"));
+}
+
+#[test]
+fn test_normalize_handle() {
+    assert_eq!(normalize_handle("@channel").unwrap(), "channel");
+    assert_eq!(normalize_handle("  channel ").unwrap(), "channel");
+    assert_eq!(normalize_handle(" @channel").unwrap(), "channel");
+}
+
+#[test]
+fn test_normalize_handle_empty() {
+    assert!(matches!(normalize_handle(""), Err(ApiError::EmptyHandle)));
+    assert!(matches!(
+        normalize_handle(" @ "),
+        Err(ApiError::EmptyHandle)
+    ));
+}
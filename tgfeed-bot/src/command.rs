@@ -15,4 +15,18 @@ pub(crate) enum Command {
     List,
     #[command(description = "Get AI summary of recent messages")]
     Summarize,
+    #[command(description = "Ask about recent posts: /ask what happened with ...")]
+    Ask(String),
+    #[command(description = "Search recent posts: /search @channel words 7d")]
+    Search(String),
+    #[command(description = "List recent summaries, /history N to re-send one")]
+    History(String),
+    #[command(
+        description = "Automatic digests: /digest daily 09:00 +03:00, /digest every 6 or /digest off"
+    )]
+    Digest(String),
+    #[command(
+        description = "Filter forwarded posts: /filter include|exclude [@channel] words or /regex/, /filter remove N, /filter clear"
+    )]
+    Filter(String),
 }
@@ -1,14 +1,33 @@
-use teloxide::payloads::SendMessageSetters;
+use teloxide::payloads::{AnswerCallbackQuerySetters, EditMessageTextSetters, SendMessageSetters};
 use teloxide::prelude::Requester;
 use teloxide::utils::command::BotCommands;
+use teloxide::utils::html;
 use tgfeed_common::command::MonitorCommand;
 use tgfeed_common::event::BotEvent;
+use tgfeed_common::filter::FilterChange;
+use tgfeed_common::html::split_html;
+use tgfeed_common::schedule::Schedule;
+use tgfeed_common::search::{SearchQuery, SearchResults};
+use tgfeed_repo::models::{Delivery, DeliveryStatus};
+use tgfeed_repo::queue::EventQueue;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::command::Command;
-use crate::utils::{format_message, split_telegram_message};
+use crate::live::{EDIT_INTERVAL, LiveMessage};
+use crate::scheduler::{SendScheduler, is_unreachable};
+use crate::search::{PAGE_SIZE, keyboard, parse_callback};
+use crate::utils::{TELEGRAM_MAX_LENGTH, format_message};
 use crate::{TgFeedBot, response};
 
+/// Summaries listed by /history
+const HISTORY_LIMIT: usize = 10;
+
+/// Queued events handled per fetch
+const EVENT_BATCH: i64 = 10;
+
+/// Longest wait for new events while the queue is empty
+const EVENT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
 pub async fn handle_command(
     bot: teloxide::prelude::Bot,
     msg: teloxide::prelude::Message,
@@ -38,7 +57,7 @@ pub async fn handle_command(
 
         let response = match BotCommands::parse(text, me.username()) {
             Ok(cmd) => match cmd {
-                Command::Start => response::start(),
+                Command::Start => this.handle_start(user_id).await,
                 Command::Help => response::help(),
                 Command::Subscribe(channel_handle) => {
                     this.handle_subscribe(user_id, channel_handle).await
@@ -49,19 +68,27 @@ pub async fn handle_command(
                 Command::List => this.handle_list(user_id).await,
 
                 Command::Summarize => match this.handle_summarize(user_id, chat_id, &bot).await {
-                    Ok(summary) => {
-                        let parts = split_telegram_message(summary);
-
-                        for part in parts {
-                            bot.send_message(msg.chat.id, part)
-                                .parse_mode(teloxide::types::ParseMode::Html)
-                                .await?;
-                        }
-
-                        return Ok(());
+                    Ok(()) => return Ok(()),
+                    Err(error_response) => error_response.to_string(),
+                },
+                Command::Ask(question) => {
+                    match this.handle_ask(user_id, &question, chat_id, &bot).await {
+                        Ok(()) => return Ok(()),
+                        Err(error_response) => error_response.to_string(),
                     }
+                }
+                Command::Search(query) => {
+                    match this.handle_search(user_id, &query, chat_id, &bot).await {
+                        Ok(()) => return Ok(()),
+                        Err(error_response) => error_response.to_string(),
+                    }
+                }
+                Command::History(number) => match this.handle_history(user_id, &number).await {
+                    Ok(text) => return send_html(&bot, chat_id, text).await,
                     Err(error_response) => error_response.to_string(),
                 },
+                Command::Digest(schedule) => this.handle_digest(user_id, &schedule).await,
+                Command::Filter(change) => this.handle_filter(user_id, &change).await,
             },
             Err(_) => response::unknown_command(),
         };
@@ -74,64 +101,393 @@ pub async fn handle_command(
     Ok(())
 }
 
+/// Turn pages of /search results
+pub async fn handle_callback(
+    bot: teloxide::prelude::Bot,
+    query: teloxide::types::CallbackQuery,
+    this: TgFeedBot,
+) -> teloxide::prelude::ResponseResult<()> {
+    let user_id = query.from.id.0 as i64;
+
+    let page = query.data.as_deref().and_then(parse_callback);
+    let notice = match (page, query.regular_message()) {
+        (Some(page), Some(message)) => this.show_search_page(&bot, user_id, message, page).await,
+        _ => None,
+    };
+
+    let mut answer = bot.answer_callback_query(query.id.clone());
+    if let Some(notice) = notice {
+        answer = answer.text(notice);
+    }
+    answer.await?;
+
+    Ok(())
+}
+
+/// Send model-generated HTML that may exceed the Telegram length limit
+async fn send_html(
+    bot: &teloxide::prelude::Bot,
+    chat_id: teloxide::types::ChatId,
+    text: String,
+) -> teloxide::prelude::ResponseResult<()> {
+    for part in split_html(&text, TELEGRAM_MAX_LENGTH) {
+        bot.send_message(chat_id, part)
+            .parse_mode(teloxide::types::ParseMode::Html)
+            .await?;
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn handle_monitor_events(
     bot: teloxide::prelude::Bot,
-    mut event_rx: mpsc::Receiver<BotEvent>,
+    repo: tgfeed_repo::Repo,
+    events: EventQueue,
 ) {
-    let retrier = retrier::RetryPolicy::exponential(tokio::time::Duration::from_secs(1));
+    let scheduler = SendScheduler::new();
 
     tracing::info!("Start listening for events from monitor...");
-    while let Some(event) = event_rx.recv().await {
-        match event {
-            BotEvent::NewMessage {
-                channel_id,
-                channel_handle,
-                text,
-                message_id,
-                subscribers,
-                entities,
-            } => {
-                let (full_text, fmt_entities) =
-                    format_message(channel_id, channel_handle, message_id, text, entities);
-
-                for user_id in subscribers {
-                    tracing::info!(
-                        %user_id,
-                        "sending message to user"
-                    );
+    while !events.is_closed() {
+        let due = match events.due(EVENT_BATCH).await {
+            Ok(due) => due,
+            Err(error) => {
+                tracing::error!(%error, "Failed to get queued events");
+                events.wait(EVENT_POLL_INTERVAL).await;
+                continue;
+            }
+        };
 
-                    if let Err(error) = retrier
-                        .retry(|| {
-                            let send_msg_fut = bot
-                                .send_message(teloxide::types::ChatId(user_id), full_text.clone())
-                                .disable_notification(true)
-                                .protect_content(true)
-                                .entities(fmt_entities.clone());
+        if due.is_empty() {
+            events.wait(EVENT_POLL_INTERVAL).await;
+            continue;
+        }
+
+        for queued in due {
+            if events.is_closed() {
+                break;
+            }
+
+            let updated = match EventQueue::decode(&queued) {
+                Ok(event) => match handle_event(&bot, &repo, &scheduler, event).await {
+                    Ok(()) => events.ack(&queued).await,
+                    Err(error) => match events.fail(&queued, &error).await {
+                        Ok(true) => {
+                            tracing::error!(%error, id = %queued.id, "Event dead-lettered");
+                            Ok(())
+                        }
+                        Ok(false) => {
+                            tracing::warn!(%error, id = %queued.id, "Event failed, will retry");
+                            Ok(())
+                        }
+                        Err(error) => Err(error),
+                    },
+                },
+                Err(error) => {
+                    tracing::error!(%error, id = %queued.id, "Dead-lettering undecodable event");
+                    events.dead_letter(&queued, &error.to_string()).await
+                }
+            };
+
+            if let Err(error) = updated {
+                tracing::error!(%error, id = %queued.id, "Failed to update queued event");
+            }
+        }
+    }
 
-                            tokio::time::timeout(tokio::time::Duration::from_secs(30), send_msg_fut)
+    tracing::warn!("Event queue closed. Stop listening for events.");
+}
+
+/// Handle a single queued event, failing if it should be retried
+async fn handle_event(
+    bot: &teloxide::prelude::Bot,
+    repo: &tgfeed_repo::Repo,
+    scheduler: &SendScheduler,
+    event: BotEvent,
+) -> Result<(), String> {
+    match event {
+        BotEvent::NewMessage {
+            channel_id,
+            channel_handle,
+            text,
+            message_id,
+            subscribers,
+            entities,
+        } => {
+            let (text, entities) =
+                format_message(channel_id, channel_handle, message_id, text, entities);
+
+            let post = Post {
+                channel_id,
+                message_id,
+                text: &text,
+                entities: &entities,
+            };
+
+            let outcomes = futures::future::join_all(
+                subscribers
+                    .iter()
+                    .map(|&user_id| deliver_post(bot, repo, scheduler, &post, user_id)),
+            )
+            .await;
+
+            // Retries skip the users the post was already sent to
+            let failed = outcomes
+                .iter()
+                .filter(|outcome| **outcome == Outcome::Failed)
+                .count();
+            if failed > 0 {
+                return Err(format!(
+                    "failed to send to {failed} of {} subscribers",
+                    subscribers.len()
+                ));
+            }
+        }
+        BotEvent::PostEdited {
+            channel_id,
+            channel_handle,
+            message_id,
+            text,
+            entities,
+        } => {
+            let (full_text, fmt_entities) =
+                format_message(channel_id, channel_handle, message_id, text, entities);
+
+            let deliveries = post_deliveries(repo, channel_id, message_id).await;
+
+            futures::future::join_all(deliveries.into_iter().map(|(user_id, chat_message_id)| {
+                let (full_text, fmt_entities) = (&full_text, &fmt_entities);
+
+                async move {
+                    tracing::info!(user_id, "editing delivered message");
+
+                    let edited = scheduler
+                        .send(user_id, || {
+                            bot.edit_message_text(
+                                teloxide::types::ChatId(user_id),
+                                chat_message_id,
+                                full_text.clone(),
+                            )
+                            .entities(fmt_entities.clone())
                         })
-                        .await
-                    {
-                        tracing::error!(
+                        .await;
+
+                    if let Err(error) = edited {
+                        tracing::warn!(
                             %error,
                             user_id,
-                            "Failed to send message to user"
-                        );
-                    } else {
-                        tracing::info!(
-                            %user_id,
-                            "message sent"
+                            "Failed to edit delivered message"
                         );
                     }
+                }
+            }))
+            .await;
+        }
+        BotEvent::PostsDeleted {
+            channel_id,
+            message_ids,
+        } => {
+            for message_id in message_ids {
+                let deliveries = post_deliveries(repo, channel_id, message_id).await;
+
+                futures::future::join_all(deliveries.into_iter().map(
+                    |(user_id, chat_message_id)| async move {
+                        tracing::info!(user_id, "marking deleted post");
+
+                        let marked = scheduler
+                            .send(user_id, || {
+                                bot.send_message(
+                                    teloxide::types::ChatId(user_id),
+                                    response::post_deleted(),
+                                )
+                                .reply_parameters(teloxide::types::ReplyParameters::new(
+                                    chat_message_id,
+                                ))
+                                .disable_notification(true)
+                            })
+                            .await;
+
+                        if let Err(error) = marked {
+                            tracing::warn!(
+                                %error,
+                                user_id,
+                                "Failed to mark deleted post"
+                            );
+                        }
+                    },
+                ))
+                .await;
+            }
+        }
+        BotEvent::Digest { user_id, summary } => {
+            tracing::info!(%user_id, "sending digest to user");
+
+            for (index, part) in split_html(&summary, TELEGRAM_MAX_LENGTH)
+                .into_iter()
+                .enumerate()
+            {
+                let sent = scheduler
+                    .send(user_id, || {
+                        bot.send_message(teloxide::types::ChatId(user_id), part.clone())
+                            .parse_mode(teloxide::types::ParseMode::Html)
+                    })
+                    .await;
+
+                if let Err(error) = sent {
+                    tracing::error!(
+                        %error,
+                        user_id,
+                        part = index + 1,
+                        "Failed to send digest to user"
+                    );
+
+                    if is_unreachable(&error) {
+                        mark_inactive(repo, user_id).await;
+                        return Ok(());
+                    }
+
+                    // A retry would repeat the parts already sent, so only a
+                    // digest that didn't reach the user at all is retried
+                    if index > 0 {
+                        return Ok(());
+                    }
 
-                    // TODO: make map for each user
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                    return Err(error.to_string());
                 }
             }
         }
     }
 
-    tracing::warn!("Monitor channel closed. Stop listening for events.");
+    Ok(())
+}
+
+/// Formatted channel post forwarded to subscribers
+struct Post<'a> {
+    channel_id: i64,
+    message_id: i32,
+    text: &'a str,
+    entities: &'a [teloxide::types::MessageEntity],
+}
+
+#[derive(PartialEq, Eq)]
+enum Outcome {
+    Sent,
+    /// Worth retrying
+    Failed,
+    /// The user can't receive messages from the bot anymore
+    Unreachable,
+}
+
+/// Send a post to a subscriber unless it was already sent, recording the
+/// attempt
+async fn deliver_post(
+    bot: &teloxide::prelude::Bot,
+    repo: &tgfeed_repo::Repo,
+    scheduler: &SendScheduler,
+    post: &Post<'_>,
+    user_id: i64,
+) -> Outcome {
+    let previous = repo
+        .get_delivery(post.channel_id, post.message_id, user_id)
+        .await
+        .unwrap_or_else(|error| {
+            tracing::error!(%error, user_id, "Failed to get delivery");
+            None
+        });
+
+    // Posts replayed after a restart are sent only once
+    if previous
+        .as_ref()
+        .is_some_and(|d| d.status == DeliveryStatus::Sent)
+    {
+        tracing::info!(%user_id, "message already delivered");
+        return Outcome::Sent;
+    }
+
+    tracing::info!(
+        %user_id,
+        "sending message to user"
+    );
+
+    let sent = scheduler
+        .send(user_id, || {
+            bot.send_message(teloxide::types::ChatId(user_id), post.text)
+                .disable_notification(true)
+                .protect_content(true)
+                .entities(post.entities.to_vec())
+        })
+        .await;
+
+    let mut delivery = Delivery {
+        channel_id: post.channel_id,
+        message_id: post.message_id,
+        user_id,
+        chat_message_id: None,
+        status: DeliveryStatus::Sent,
+        attempts: previous.map_or(0, |d| d.attempts) + 1,
+        last_error: None,
+        attempted_at: chrono::Utc::now(),
+    };
+
+    let outcome = match sent {
+        Ok(sent) => {
+            tracing::info!(
+                %user_id,
+                "message sent"
+            );
+            delivery.chat_message_id = Some(sent.id.0);
+            Outcome::Sent
+        }
+        Err(error) => {
+            tracing::error!(
+                %error,
+                user_id,
+                "Failed to send message to user"
+            );
+            delivery.status = DeliveryStatus::Failed;
+            delivery.last_error = Some(error.to_string());
+
+            if is_unreachable(&error) {
+                mark_inactive(repo, user_id).await;
+                Outcome::Unreachable
+            } else {
+                Outcome::Failed
+            }
+        }
+    };
+
+    if let Err(error) = repo.record_delivery(delivery).await {
+        tracing::error!(%error, user_id, "Failed to record delivery");
+    }
+
+    outcome
+}
+
+/// Stop routing posts to a user the bot can't reach until they /start again
+async fn mark_inactive(repo: &tgfeed_repo::Repo, user_id: i64) {
+    tracing::warn!(user_id, "user can't be reached, marking inactive");
+
+    if let Err(error) = repo.set_user_active(user_id, false).await {
+        tracing::error!(%error, user_id, "Failed to mark user inactive");
+    }
+}
+
+/// Users a channel post was sent to and the bot messages they got, empty if
+/// the lookup fails
+async fn post_deliveries(
+    repo: &tgfeed_repo::Repo,
+    channel_id: i64,
+    message_id: i32,
+) -> Vec<(i64, teloxide::types::MessageId)> {
+    match repo.get_post_deliveries(channel_id, message_id).await {
+        Ok(deliveries) => deliveries
+            .into_iter()
+            .filter_map(|d| Some((d.user_id, teloxide::types::MessageId(d.chat_message_id?))))
+            .collect(),
+        Err(error) => {
+            tracing::error!(%error, channel_id, message_id, "Failed to get deliveries");
+            Vec::new()
+        }
+    }
 }
 
 macro_rules! send_logging_error {
@@ -159,6 +515,15 @@ macro_rules! send_logging_error {
 }
 
 impl TgFeedBot {
+    /// Resume forwarding to a user who was marked inactive
+    async fn handle_start(&self, user_id: i64) -> String {
+        if let Err(error) = self.repo.set_user_active(user_id, true).await {
+            tracing::error!(%error, user_id, "Failed to mark user active");
+        }
+
+        response::start()
+    }
+
     async fn handle_subscribe(&self, user_id: i64, channel_handle: String) -> String {
         let channel_handle = channel_handle.trim().trim_start_matches('@').to_string();
         if channel_handle.is_empty() {
@@ -228,28 +593,346 @@ impl TgFeedBot {
         user_id: i64,
         chat_id: teloxide::types::ChatId,
         bot: &teloxide::prelude::Bot,
-    ) -> anyhow::Result<String> {
-        if let Err(error) = self.rate_limiters.summarize.check_key(&user_id) {
-            tracing::warn!(%user_id, %error, "/summarize rate limit reached");
+    ) -> anyhow::Result<()> {
+        if let Err(wait) = self.rate_limiters.summarize.check_key(&user_id) {
+            tracing::warn!(%user_id, ?wait, "/summarize rate limit reached");
             anyhow::bail!("⏳ /summarize is limited to once per hour")
-        } else {
-            // TODO: edit message instead
-            let _message = bot
-                .send_message(chat_id, "⏳ Generating summary...")
-                .await?;
+        }
 
-            let (tx, rx) = oneshot::channel();
+        self.request_summary(user_id, chat_id, bot).await
+    }
+
+    async fn request_summary(
+        &self,
+        user_id: i64,
+        chat_id: teloxide::types::ChatId,
+        bot: &teloxide::prelude::Bot,
+    ) -> anyhow::Result<()> {
+        // Attempts the monitor didn't complete don't count against the hourly
+        // quota. A stored summary does, even if showing it fails
+        let mut live = match LiveMessage::send(bot, chat_id, "⏳ Generating summary...").await {
+            Ok(live) => live,
+            Err(error) => {
+                self.rate_limiters.summarize.refund(&user_id);
+                return Err(error.into());
+            }
+        };
+
+        match self.stream_summary(user_id, &mut live).await {
+            Ok(summary) => Ok(live.finish(summary).await?),
+            Err(error) => {
+                self.rate_limiters.summarize.refund(&user_id);
+
+                // The error is answered in a new message instead
+                if let Err(error) = live.discard().await {
+                    tracing::warn!(%error, "failed to remove summary placeholder");
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Request a summary, previewing the text in `live` as it is generated
+    async fn stream_summary(&self, user_id: i64, live: &mut LiveMessage) -> anyhow::Result<String> {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = oneshot::channel();
+
+        send_logging_error!(bail, self, MonitorCommand::Summarize {
+            user_id,
+            progress: Some(progress_tx),
+            response: tx,
+        });
+
+        let mut text = String::new();
+        let mut last_edit = tokio::time::Instant::now();
+
+        let result = loop {
+            tokio::select! {
+                Some(delta) = progress_rx.recv() => {
+                    text.push_str(&delta);
+
+                    if last_edit.elapsed() >= EDIT_INTERVAL {
+                        if let Err(error) = live.preview(&text).await {
+                            tracing::warn!(%error, "failed to update summary preview");
+                        }
+                        last_edit = tokio::time::Instant::now();
+                    }
+                }
+                result = &mut rx => break result,
+            }
+        };
 
-            send_logging_error!(bail, self, MonitorCommand::Summarize {
+        match result {
+            Ok(Ok(summary)) => Ok(summary),
+            Ok(Err(error)) => anyhow::bail!("❌ Failed to summarize: {error}"),
+            Err(_) => anyhow::bail!(response::internal_server_error()),
+        }
+    }
+
+    async fn handle_ask(
+        &self,
+        user_id: i64,
+        question: &str,
+        chat_id: teloxide::types::ChatId,
+        bot: &teloxide::prelude::Bot,
+    ) -> anyhow::Result<()> {
+        let question = question.trim();
+        if question.is_empty() {
+            anyhow::bail!(response::ask_usage())
+        }
+
+        if let Err(wait) = self.rate_limiters.ask.check_key(&user_id) {
+            tracing::warn!(%user_id, ?wait, "/ask rate limit reached");
+            anyhow::bail!("⏳ /ask is limited to once per minute")
+        }
+
+        let result = self.request_answer(user_id, question, chat_id, bot).await;
+
+        if result.is_err() {
+            self.rate_limiters.ask.refund(&user_id);
+        }
+
+        result
+    }
+
+    async fn request_answer(
+        &self,
+        user_id: i64,
+        question: &str,
+        chat_id: teloxide::types::ChatId,
+        bot: &teloxide::prelude::Bot,
+    ) -> anyhow::Result<()> {
+        let live = LiveMessage::send(bot, chat_id, "🔎 Searching your channels...").await?;
+
+        let (tx, rx) = oneshot::channel();
+
+        let result = async {
+            send_logging_error!(bail, self, MonitorCommand::Ask {
                 user_id,
+                question: question.to_string(),
                 response: tx,
             });
 
             match rx.await {
-                Ok(Ok(summary)) => Ok(summary),
-                Ok(Err(error)) => anyhow::bail!("❌ Failed to summarize: {error}"),
+                Ok(Ok(answer)) => Ok(answer),
+                Ok(Err(error)) => anyhow::bail!("❌ Failed to answer: {error}"),
                 Err(_) => anyhow::bail!(response::internal_server_error()),
             }
         }
+        .await;
+
+        match result {
+            Ok(answer) => Ok(live.finish(answer).await?),
+            Err(error) => {
+                if let Err(error) = live.discard().await {
+                    tracing::warn!(%error, "failed to remove answer placeholder");
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn handle_search(
+        &self,
+        user_id: i64,
+        query: &str,
+        chat_id: teloxide::types::ChatId,
+        bot: &teloxide::prelude::Bot,
+    ) -> anyhow::Result<()> {
+        let query = match query.parse::<SearchQuery>() {
+            Ok(query) => query,
+            Err(error) => anyhow::bail!(response::search_usage(&error)),
+        };
+
+        let results = self.request_search(user_id, query.clone(), 0).await?;
+
+        let mut request = bot
+            .send_message(chat_id, response::search_results(&results, 0))
+            .parse_mode(teloxide::types::ParseMode::Html)
+            .link_preview_options(no_link_preview());
+        if let Some(keyboard) = keyboard(0, results.total) {
+            request = request.reply_markup(keyboard);
+        }
+
+        let message = request.await?;
+        self.searches.start(user_id, message.id, query);
+
+        Ok(())
+    }
+
+    /// Replace the results in `message` with `page`, returning a notice for
+    /// the user if that isn't possible
+    async fn show_search_page(
+        &self,
+        bot: &teloxide::prelude::Bot,
+        user_id: i64,
+        message: &teloxide::types::Message,
+        page: usize,
+    ) -> Option<String> {
+        if self.rate_limiters.commands.check_key(&user_id).is_err() {
+            return Some("⏳ Please wait a moment".to_string());
+        }
+
+        let Some(query) = self.searches.get(user_id, message.id) else {
+            return Some("This search has expired, run /search again".to_string());
+        };
+
+        let results = match self.request_search(user_id, query, page).await {
+            Ok(results) => results,
+            Err(error) => return Some(error.to_string()),
+        };
+
+        let mut request = bot
+            .edit_message_text(
+                message.chat.id,
+                message.id,
+                response::search_results(&results, page),
+            )
+            .parse_mode(teloxide::types::ParseMode::Html)
+            .link_preview_options(no_link_preview());
+        if let Some(keyboard) = keyboard(page, results.total) {
+            request = request.reply_markup(keyboard);
+        }
+
+        if let Err(error) = request.await {
+            tracing::warn!(%error, "failed to show search page");
+        }
+
+        None
+    }
+
+    async fn request_search(
+        &self,
+        user_id: i64,
+        query: SearchQuery,
+        page: usize,
+    ) -> anyhow::Result<SearchResults> {
+        let (tx, rx) = oneshot::channel();
+
+        send_logging_error!(bail, self, MonitorCommand::Search {
+            user_id,
+            query,
+            offset: (page * PAGE_SIZE) as u64,
+            limit: PAGE_SIZE,
+            response: tx,
+        });
+
+        match rx.await {
+            Ok(Ok(results)) => Ok(results),
+            Ok(Err(error)) => anyhow::bail!("❌ Failed to search: {error}"),
+            Err(_) => anyhow::bail!(response::internal_server_error()),
+        }
+    }
+
+    /// List stored summaries, or re-send one by its number in the list
+    async fn handle_history(&self, user_id: i64, number: &str) -> anyhow::Result<String> {
+        let number = match number.trim() {
+            "" => None,
+            number => match number.parse::<usize>() {
+                Ok(number) if number > HISTORY_LIMIT => {
+                    anyhow::bail!(response::history_not_found(number))
+                }
+                Ok(number) if number > 0 => Some(number),
+                _ => anyhow::bail!(response::history_usage()),
+            },
+        };
+
+        let (tx, rx) = oneshot::channel();
+
+        send_logging_error!(bail, self, MonitorCommand::SummaryHistory {
+            user_id,
+            limit: number.unwrap_or(HISTORY_LIMIT),
+            response: tx,
+        });
+
+        let mut entries = match rx.await {
+            Ok(Ok(entries)) => entries,
+            Ok(Err(error)) => anyhow::bail!("❌ Failed to load history: {error}"),
+            Err(_) => anyhow::bail!(response::internal_server_error()),
+        };
+
+        match number {
+            None => Ok(response::history(&entries)),
+            Some(number) if entries.len() == number => Ok(entries.swap_remove(number - 1).html),
+            Some(number) => anyhow::bail!(response::history_not_found(number)),
+        }
+    }
+
+    /// Show, set or turn off the automatic digest schedule
+    async fn handle_digest(&self, user_id: i64, schedule: &str) -> String {
+        let schedule = match schedule.trim() {
+            "" => return self.show_digest(user_id).await,
+            "off" => None,
+            schedule => match schedule.parse::<Schedule>() {
+                Ok(schedule) => Some(schedule),
+                Err(error) => return response::digest_usage(&error),
+            },
+        };
+
+        let (tx, rx) = oneshot::channel();
+
+        send_logging_error!(self, MonitorCommand::SetDigest {
+            user_id,
+            schedule,
+            response: tx,
+        });
+
+        match rx.await {
+            Ok(Ok(())) => response::digest(schedule),
+            Ok(Err(e)) => format!("❌ Failed to update digest: {e}"),
+            Err(_) => response::internal_server_error(),
+        }
+    }
+
+    /// List the user's filters after applying an optional change
+    async fn handle_filter(&self, user_id: i64, change: &str) -> String {
+        let change = match change.trim() {
+            "" => None,
+            change => match change.parse::<FilterChange>() {
+                Ok(change) => Some(change),
+                Err(error) => return response::filter_usage(&error),
+            },
+        };
+
+        let (tx, rx) = oneshot::channel();
+
+        send_logging_error!(self, MonitorCommand::Filters {
+            user_id,
+            change,
+            response: tx,
+        });
+
+        match rx.await {
+            Ok(Ok(filters)) => response::filters(&filters),
+            Ok(Err(e)) => format!("❌ Failed to update filters: {}", html::escape(&e)),
+            Err(_) => response::internal_server_error(),
+        }
+    }
+
+    async fn show_digest(&self, user_id: i64) -> String {
+        let (tx, rx) = oneshot::channel();
+
+        send_logging_error!(self, MonitorCommand::GetDigest {
+            user_id,
+            response: tx,
+        });
+
+        match rx.await {
+            Ok(Ok(schedule)) => response::digest(schedule),
+            Ok(Err(e)) => format!("❌ Failed to get digest: {e}"),
+            Err(_) => response::internal_server_error(),
+        }
+    }
+}
+
+fn no_link_preview() -> teloxide::types::LinkPreviewOptions {
+    teloxide::types::LinkPreviewOptions {
+        is_disabled: true,
+        url: None,
+        prefer_small_media: false,
+        prefer_large_media: false,
+        show_above_text: false,
     }
 }
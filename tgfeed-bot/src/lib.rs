@@ -1,8 +1,11 @@
 mod command;
 mod config;
 mod handler;
+mod live;
 mod rate_limit;
 mod response;
+mod scheduler;
+mod search;
 mod utils;
 
 #[cfg(test)]
@@ -15,49 +18,79 @@ use teloxide::dispatching::UpdateFilterExt;
 use teloxide::prelude::Requester;
 use teloxide::utils::command::BotCommands;
 use tgfeed_common::command::MonitorCommand;
-use tgfeed_common::event::BotEvent;
+use tgfeed_common::health::Liveness;
+use tgfeed_repo::queue::EventQueue;
 use tokio::sync::mpsc;
 
 use crate::command::Command;
 use crate::rate_limit::RateLimiters;
+use crate::search::Searches;
 
 #[derive(Clone)]
 pub struct TgFeedBot {
     bot_token: String,
     monitor_tx: mpsc::Sender<MonitorCommand>,
+    repo: tgfeed_repo::Repo,
     rate_limiters: Arc<RateLimiters>,
+    searches: Arc<Searches>,
+    status: BotStatus,
+}
+
+/// Liveness of the bot's long-running loops
+#[derive(Clone, Default)]
+pub struct BotStatus {
+    pub dispatcher: Liveness,
+    pub events: Liveness,
 }
 
 impl TgFeedBot {
-    pub fn new(config: &Config, monitor_tx: mpsc::Sender<MonitorCommand>) -> Self {
+    pub fn new(
+        config: &Config,
+        repo: tgfeed_repo::Repo,
+        monitor_tx: mpsc::Sender<MonitorCommand>,
+    ) -> Self {
         let rate_limiters = Arc::new(RateLimiters::new());
 
         Self {
             monitor_tx,
+            repo,
             rate_limiters,
+            searches: Arc::default(),
             bot_token: config.token.clone(),
+            status: BotStatus::default(),
         }
     }
 
-    pub async fn run(
-        self,
-        event_rx: mpsc::Receiver<BotEvent>,
-    ) -> Result<(), teloxide::RequestError> {
+    pub fn status(&self) -> BotStatus {
+        self.status.clone()
+    }
+
+    pub async fn run(self, events: EventQueue) -> Result<(), teloxide::RequestError> {
         tracing::info!("Starting Telegram bot...");
 
         let bot = teloxide::prelude::Bot::new(&self.bot_token);
 
         bot.set_my_commands(Command::bot_commands()).await?;
 
-        let handler = teloxide::prelude::Update::filter_message().endpoint(handler::handle_command);
+        let handler = teloxide::prelude::dptree::entry()
+            .branch(teloxide::prelude::Update::filter_message().endpoint(handler::handle_command))
+            .branch(
+                teloxide::prelude::Update::filter_callback_query()
+                    .endpoint(handler::handle_callback),
+            );
 
         let event_handle = {
             let bot = bot.clone();
+            let repo = self.repo.clone();
+            let alive = self.status.events.clone();
             tokio::spawn(async move {
-                handler::handle_monitor_events(bot, event_rx).await;
+                let _alive = alive.guard();
+                handler::handle_monitor_events(bot, repo, events).await;
             })
         };
 
+        let dispatcher_alive = self.status.dispatcher.guard();
+
         teloxide::prelude::Dispatcher::builder(bot, handler)
             .dependencies(teloxide::prelude::dptree::deps![self])
             .enable_ctrlc_handler()
@@ -65,6 +98,8 @@ impl TgFeedBot {
             .dispatch()
             .await;
 
+        drop(dispatcher_alive);
+
         event_handle.await.expect("event handler loop failed");
 
         Ok(())
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use teloxide::payloads::{EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{Bot, Requester, ResponseResult};
+use teloxide::types::{ChatId, MessageId, ParseMode};
+use teloxide::{ApiError, RequestError};
+use tgfeed_common::html::split_html;
+
+use crate::utils::{TELEGRAM_MAX_LENGTH, split_telegram_message};
+
+/// Minimum delay between edits, Telegram throttles faster ones
+pub(crate) const EDIT_INTERVAL: Duration = Duration::from_millis(1500);
+
+/// Text edited in place as it grows, overflowing into follow-up messages
+/// past the Telegram length limit
+pub(crate) struct LiveMessage {
+    bot: Bot,
+    chat_id: ChatId,
+    /// Sent messages with their current text
+    messages: Vec<(MessageId, String)>,
+}
+
+impl LiveMessage {
+    pub(crate) async fn send(
+        bot: &Bot,
+        chat_id: ChatId,
+        placeholder: &str,
+    ) -> ResponseResult<Self> {
+        let message = bot.send_message(chat_id, placeholder).await?;
+
+        Ok(Self {
+            bot: bot.clone(),
+            chat_id,
+            messages: vec![(message.id, placeholder.to_string())],
+        })
+    }
+
+    /// Show incomplete text. It may end inside an HTML tag, so it's shown as
+    /// plain text
+    pub(crate) async fn preview(&mut self, text: &str) -> ResponseResult<()> {
+        self.show(split_telegram_message(text.to_string()), None)
+            .await
+    }
+
+    /// Show the complete HTML text, sanitized for Telegram
+    pub(crate) async fn finish(mut self, text: String) -> ResponseResult<()> {
+        let parts = split_html(&text, TELEGRAM_MAX_LENGTH);
+        let count = parts.len();
+
+        self.show(parts, Some(ParseMode::Html)).await?;
+
+        for (id, _) in self.messages.drain(count..) {
+            self.bot.delete_message(self.chat_id, id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete every sent message
+    pub(crate) async fn discard(self) -> ResponseResult<()> {
+        for (id, _) in self.messages {
+            self.bot.delete_message(self.chat_id, id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn show(
+        &mut self,
+        parts: Vec<String>,
+        parse_mode: Option<ParseMode>,
+    ) -> ResponseResult<()> {
+        for (i, part) in parts.into_iter().enumerate() {
+            match self.messages.get_mut(i) {
+                Some((id, current)) => {
+                    // Switching to HTML re-renders even an unchanged text
+                    if *current == part && parse_mode.is_none() {
+                        continue;
+                    }
+
+                    let mut edit = self.bot.edit_message_text(self.chat_id, *id, &part);
+                    if let Some(parse_mode) = parse_mode {
+                        edit = edit.parse_mode(parse_mode);
+                    }
+
+                    match edit.await {
+                        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => (),
+                        Err(error) => return Err(error),
+                    }
+
+                    *current = part;
+                }
+                None => {
+                    let mut send = self.bot.send_message(self.chat_id, &part);
+                    if let Some(parse_mode) = parse_mode {
+                        send = send.parse_mode(parse_mode);
+                    }
+
+                    let message = send.await?;
+                    self.messages.push((message.id, part));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
 use std::num::NonZeroU32;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use governor::clock::DefaultClock;
 use governor::state::keyed::DashMapStateStore;
@@ -9,7 +11,8 @@ pub type KeyedRateLimiter = RateLimiter<i64, DashMapStateStore<i64>, DefaultCloc
 
 pub struct RateLimiters {
     pub commands: KeyedRateLimiter,
-    pub summarize: KeyedRateLimiter,
+    pub summarize: RefundableRateLimiter,
+    pub ask: RefundableRateLimiter,
 }
 
 impl RateLimiters {
@@ -20,11 +23,45 @@ impl RateLimiters {
                     .unwrap()
                     .allow_burst(NonZeroU32::new(1).unwrap()),
             ),
-            summarize: RateLimiter::keyed(
-                Quota::with_period(Duration::from_secs(3600))
-                    .unwrap()
-                    .allow_burst(NonZeroU32::new(1).unwrap()),
-            ),
+            summarize: RefundableRateLimiter::new(Duration::from_secs(3600)),
+            ask: RefundableRateLimiter::new(Duration::from_secs(60)),
         }
     }
 }
+
+/// Once-per-period keyed limiter whose token can be handed back when the
+/// guarded operation fails
+pub struct RefundableRateLimiter {
+    period: Duration,
+    last: Mutex<HashMap<i64, Instant>>,
+}
+
+impl RefundableRateLimiter {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take the token for `key`, or return how long until it is available
+    pub fn check_key(&self, key: &i64) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut last = self.last.lock().unwrap();
+
+        if let Some(taken) = last.get(key) {
+            let elapsed = now.duration_since(*taken);
+            if elapsed < self.period {
+                return Err(self.period - elapsed);
+            }
+        }
+
+        last.insert(*key, now);
+        Ok(())
+    }
+
+    /// Give the token for `key` back
+    pub fn refund(&self, key: &i64) {
+        self.last.lock().unwrap().remove(key);
+    }
+}
@@ -1,6 +1,16 @@
 use teloxide::utils::command::BotCommands;
+use teloxide::utils::html;
+use tgfeed_common::command::SummaryEntry;
+use tgfeed_common::filter::{Filter, InvalidFilter};
+use tgfeed_common::schedule::{InvalidSchedule, Schedule};
+use tgfeed_common::search::{InvalidSearch, SearchResults};
+use tgfeed_common::utils::post_url;
 
 use crate::command::Command;
+use crate::search::page_count;
+
+/// Characters of a post shown in search results
+const SNIPPET_CHARS: usize = 300;
 
 pub fn start() -> String {
     "👋 Hello! This is a Telegram channels aggregator. Run /help to see the available commands."
@@ -22,3 +32,123 @@ pub fn unknown_command() -> String {
 pub fn internal_server_error() -> String {
     "❌ Internal server error".to_string()
 }
+
+pub fn post_deleted() -> String {
+    "🗑 The channel deleted this post".to_string()
+}
+
+pub fn ask_usage() -> String {
+    "Usage: /ask your question".to_string()
+}
+
+pub fn history_usage() -> String {
+    "Usage: /history or /history N".to_string()
+}
+
+pub fn history_not_found(number: usize) -> String {
+    format!("❌ No summary #{number} in your history")
+}
+
+/// Numbered list of stored summaries, newest first
+pub fn history(entries: &[SummaryEntry]) -> String {
+    if entries.is_empty() {
+        return "📭 No summaries yet. Run /summarize to get one.".to_string();
+    }
+
+    let mut text = "📚 Recent summaries:\n".to_string();
+
+    for (i, entry) in entries.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}. {} — {} ({} channels, {})",
+            i + 1,
+            entry.since.format("%d.%m %H:%M"),
+            entry.until.format("%d.%m %H:%M UTC"),
+            entry.channels.len(),
+            html::escape(&entry.model),
+        ));
+    }
+
+    text.push_str("\n\nRe-send one with /history N");
+    text
+}
+
+pub fn digest(schedule: Option<Schedule>) -> String {
+    match schedule {
+        Some(schedule) => format!("🗓 Digest is delivered {schedule}"),
+        None => "🗓 Automatic digests are off".to_string(),
+    }
+}
+
+pub fn digest_usage(error: &InvalidSchedule) -> String {
+    format!("❌ Invalid schedule: {}", html::escape(&error.to_string()))
+}
+
+/// Numbered list of filter rules, positions as taken by /filter remove
+pub fn filters(filters: &[Filter]) -> String {
+    if filters.is_empty() {
+        return "🧹 No filters, all posts are forwarded. Add one with /filter include words or /filter exclude words".to_string();
+    }
+
+    let mut text = "🧹 Your filters:\n".to_string();
+
+    for (i, filter) in filters.iter().enumerate() {
+        text.push_str(&format!(
+            "\n{}. <code>{}</code>",
+            i + 1,
+            html::escape(&filter.to_string())
+        ));
+    }
+
+    text.push_str("\n\nExcluded posts are never forwarded. With include filters only matching posts are.\nRemove one with /filter remove N");
+    text
+}
+
+pub fn filter_usage(error: &InvalidFilter) -> String {
+    format!(
+        "❌ Invalid filter: {}\nExample: /filter exclude @channel /#ad\\b/",
+        html::escape(&error.to_string())
+    )
+}
+
+pub fn search_usage(error: &InvalidSearch) -> String {
+    format!(
+        "❌ Invalid search: {}\nExample: /search @channel rates 7d",
+        html::escape(&error.to_string())
+    )
+}
+
+/// Page `page` of search results, with links to the posts
+pub fn search_results(results: &SearchResults, page: usize) -> String {
+    if results.total == 0 {
+        return "🔎 Nothing found".to_string();
+    }
+
+    let mut text = format!(
+        "🔎 Found {} posts, page {}/{}",
+        results.total,
+        page + 1,
+        page_count(results.total)
+    );
+
+    if results.posts.is_empty() {
+        text.push_str("\n\nNo more results");
+        return text;
+    }
+
+    for post in &results.posts {
+        let mut snippet = post.text.chars().take(SNIPPET_CHARS).collect::<String>();
+        if post.text.chars().nth(SNIPPET_CHARS).is_some() {
+            snippet.push('…');
+        }
+
+        text.push_str(&format!(
+            "\n\n📢 <b>@{}</b> · {}\n{}\n<a href=\"{}\">Open post</a>",
+            html::escape(&post.channel_handle),
+            post.date.format("%d.%m %H:%M UTC"),
+            html::escape(&snippet),
+            post_url(post.channel_id, post.message_id),
+        ));
+    }
+
+    text
+}
@@ -0,0 +1,130 @@
+//! Pacing of messages the bot sends on its own, like forwarded posts and
+//! digests, to stay within Telegram's limits.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use teloxide::{ApiError, RequestError};
+use tokio::time::Instant;
+
+use crate::rate_limit::KeyedRateLimiter;
+
+/// Messages per second Telegram accepts from a bot across all chats
+const GLOBAL_PER_SECOND: u32 = 30;
+
+/// Interval Telegram expects between messages to the same chat
+const CHAT_PERIOD: Duration = Duration::from_secs(1);
+
+/// Longest wait for Telegram to answer a single request
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Flood-wait errors waited out before a request is given up
+const MAX_RETRY_AFTER: usize = 3;
+
+/// Global and per-chat rate limits, applied independently so different
+/// chats can be sent to concurrently
+pub struct SendScheduler {
+    global: DefaultDirectRateLimiter,
+    chats: KeyedRateLimiter,
+    /// Chats Telegram asked to wait for, until when
+    paused: Mutex<HashMap<i64, Instant>>,
+}
+
+impl SendScheduler {
+    pub fn new() -> Self {
+        Self::with_limits(NonZeroU32::new(GLOBAL_PER_SECOND).unwrap(), CHAT_PERIOD)
+    }
+
+    pub fn with_limits(global_per_second: NonZeroU32, chat_period: Duration) -> Self {
+        Self {
+            global: RateLimiter::direct(Quota::per_second(global_per_second)),
+            chats: RateLimiter::keyed(
+                Quota::with_period(chat_period)
+                    .unwrap()
+                    .allow_burst(NonZeroU32::new(1).unwrap()),
+            ),
+            paused: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Send a request to `chat_id` once the limits allow, waiting out
+    /// `RetryAfter` errors
+    pub async fn send<T, R>(
+        &self,
+        chat_id: i64,
+        mut request: impl FnMut() -> R,
+    ) -> Result<T, RequestError>
+    where
+        R: IntoFuture<Output = Result<T, RequestError>>,
+    {
+        let mut flood_waits = 0;
+
+        loop {
+            self.ready(chat_id).await;
+
+            let result = tokio::time::timeout(SEND_TIMEOUT, request().into_future())
+                .await
+                .unwrap_or_else(|_| Err(timed_out()));
+
+            match result {
+                Err(RequestError::RetryAfter(wait)) if flood_waits < MAX_RETRY_AFTER => {
+                    flood_waits += 1;
+                    tracing::warn!(chat_id, %wait, "Telegram asked to slow down");
+                    self.pause(chat_id, wait.duration());
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn ready(&self, chat_id: i64) {
+        if let Some(until) = self.paused_until(chat_id) {
+            tokio::time::sleep_until(until).await;
+        }
+
+        self.chats.until_key_ready(&chat_id).await;
+        self.global.until_ready().await;
+    }
+
+    fn paused_until(&self, chat_id: i64) -> Option<Instant> {
+        let now = Instant::now();
+        let mut paused = self.paused.lock().unwrap();
+
+        paused.retain(|_, until| *until > now);
+        paused.get(&chat_id).copied()
+    }
+
+    fn pause(&self, chat_id: i64, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut paused = self.paused.lock().unwrap();
+
+        let paused_until = paused.entry(chat_id).or_insert(until);
+        *paused_until = (*paused_until).max(until);
+    }
+}
+
+/// Whether `error` means the chat will never accept messages from the bot,
+/// so retrying is pointless
+pub fn is_unreachable(error: &RequestError) -> bool {
+    matches!(
+        error,
+        RequestError::Api(
+            ApiError::BotBlocked
+                | ApiError::ChatNotFound
+                | ApiError::UserNotFound
+                | ApiError::UserDeactivated
+                | ApiError::CantInitiateConversation
+                | ApiError::BotKicked
+        )
+    )
+}
+
+fn timed_out() -> RequestError {
+    RequestError::Io(Arc::new(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        "request timed out",
+    )))
+}
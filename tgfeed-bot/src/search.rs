@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId};
+use tgfeed_common::search::SearchQuery;
+
+/// Posts per page of /search results
+pub(crate) const PAGE_SIZE: usize = 5;
+
+const CALLBACK_PREFIX: &str = "search:";
+
+/// Latest /search of every user. Callback data is too short for the query,
+/// so page buttons only carry the page number
+#[derive(Default)]
+pub(crate) struct Searches {
+    active: Mutex<HashMap<i64, (MessageId, SearchQuery)>>,
+}
+
+impl Searches {
+    /// Remember `query` as shown in `message_id`, replacing the previous one
+    pub(crate) fn start(&self, user_id: i64, message_id: MessageId, query: SearchQuery) {
+        self.active
+            .lock()
+            .unwrap()
+            .insert(user_id, (message_id, query));
+    }
+
+    /// Query shown in `message_id`, unless the user searched again since
+    pub(crate) fn get(&self, user_id: i64, message_id: MessageId) -> Option<SearchQuery> {
+        match self.active.lock().unwrap().get(&user_id) {
+            Some((id, query)) if *id == message_id => Some(query.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Previous/next buttons, `None` when everything fits one page
+pub(crate) fn keyboard(page: usize, total: u64) -> Option<InlineKeyboardMarkup> {
+    let pages = page_count(total);
+    if pages <= 1 {
+        return None;
+    }
+
+    let mut row = Vec::new();
+    if page > 0 {
+        row.push(InlineKeyboardButton::callback(
+            "◀️",
+            format!("{CALLBACK_PREFIX}{}", page - 1),
+        ));
+    }
+    row.push(InlineKeyboardButton::callback(
+        format!("{}/{pages}", page + 1),
+        format!("{CALLBACK_PREFIX}{page}"),
+    ));
+    if page + 1 < pages {
+        row.push(InlineKeyboardButton::callback(
+            "▶️",
+            format!("{CALLBACK_PREFIX}{}", page + 1),
+        ));
+    }
+
+    Some(InlineKeyboardMarkup::new([row]))
+}
+
+/// Page requested by a button
+pub(crate) fn parse_callback(data: &str) -> Option<usize> {
+    data.strip_prefix(CALLBACK_PREFIX)?.parse().ok()
+}
+
+pub(crate) fn page_count(total: u64) -> usize {
+    (total as usize).div_ceil(PAGE_SIZE)
+}
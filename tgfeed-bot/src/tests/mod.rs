@@ -1 +1,5 @@
 mod formatting;
+mod rate_limit;
+mod response;
+mod scheduler;
+mod search;
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use crate::rate_limit::RefundableRateLimiter;
+
+#[test]
+fn test_once_per_period_per_key() {
+    let limiter = RefundableRateLimiter::new(Duration::from_secs(3600));
+
+    assert!(limiter.check_key(&1).is_ok());
+    assert!(limiter.check_key(&2).is_ok());
+
+    let wait = limiter.check_key(&1).unwrap_err();
+    assert!(wait > Duration::from_secs(3500));
+}
+
+#[test]
+fn test_refund_returns_token() {
+    let limiter = RefundableRateLimiter::new(Duration::from_secs(3600));
+
+    assert!(limiter.check_key(&1).is_ok());
+    limiter.refund(&1);
+    assert!(limiter.check_key(&1).is_ok());
+    assert!(limiter.check_key(&1).is_err());
+}
+
+#[test]
+fn test_token_available_after_period() {
+    let limiter = RefundableRateLimiter::new(Duration::ZERO);
+
+    assert!(limiter.check_key(&1).is_ok());
+    assert!(limiter.check_key(&1).is_ok());
+}
@@ -0,0 +1,79 @@
+use chrono::TimeZone;
+use tgfeed_common::command::SummaryEntry;
+use tgfeed_common::filter::Filter;
+use tgfeed_common::search::{FoundPost, SearchResults};
+
+use crate::response;
+
+#[test]
+fn test_history_empty() {
+    assert!(response::history(&[]).contains("/summarize"));
+}
+
+#[test]
+fn test_history_numbered_newest_first() {
+    let entry = |day, model: &str| SummaryEntry {
+        since: chrono::Utc.with_ymd_and_hms(2025, 1, day, 8, 0, 0).unwrap(),
+        until: chrono::Utc
+            .with_ymd_and_hms(2025, 1, day, 20, 30, 0)
+            .unwrap(),
+        channels: vec!["first".to_string(), "second".to_string()],
+        model: model.to_string(),
+        html: String::new(),
+        created_at: chrono::Utc
+            .with_ymd_and_hms(2025, 1, day, 20, 31, 0)
+            .unwrap(),
+    };
+
+    let text = response::history(&[entry(2, "<model>"), entry(1, "model")]);
+
+    assert!(text.contains("\n1. 02.01 08:00 — 02.01 20:30 UTC (2 channels, &lt;model&gt;)"));
+    assert!(text.contains("\n2. 01.01 08:00 — 01.01 20:30 UTC (2 channels, model)"));
+    assert!(text.ends_with("/history N"));
+}
+
+#[test]
+fn test_search_results_empty() {
+    let results = SearchResults {
+        posts: Vec::new(),
+        total: 0,
+    };
+
+    assert_eq!(response::search_results(&results, 0), "🔎 Nothing found");
+}
+
+#[test]
+fn test_search_results_link_posts() {
+    let results = SearchResults {
+        posts: vec![FoundPost {
+            channel_id: 100,
+            channel_handle: "news".to_string(),
+            message_id: 7,
+            text: format!("<b>{}", "x".repeat(400)),
+            date: chrono::Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 0).unwrap(),
+        }],
+        total: 6,
+    };
+
+    let text = response::search_results(&results, 1);
+
+    assert!(text.starts_with("🔎 Found 6 posts, page 2/2"));
+    assert!(text.contains("📢 <b>@news</b> · 02.01 03:04 UTC\n&lt;b&gt;xxx"));
+    assert!(text.contains("x…\n<a href=\"https://t.me/c/100/7\">Open post</a>"));
+}
+
+#[test]
+fn test_filters_empty() {
+    assert!(response::filters(&[]).contains("/filter include"));
+}
+
+#[test]
+fn test_filters_numbered_and_escaped() {
+    let filters =
+        ["include rates", "exclude @news /<b>ad/"].map(|filter| filter.parse::<Filter>().unwrap());
+
+    let text = response::filters(&filters);
+
+    assert!(text.contains("1. <code>include rates</code>"));
+    assert!(text.contains("2. <code>exclude @news /&lt;b&gt;ad/</code>"));
+}
@@ -0,0 +1,77 @@
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use teloxide::RequestError;
+use teloxide::types::Seconds;
+
+use crate::scheduler::{SendScheduler, is_unreachable};
+
+fn scheduler() -> SendScheduler {
+    SendScheduler::with_limits(NonZeroU32::new(1000).unwrap(), Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn test_retry_after_is_waited_out() {
+    let calls = AtomicUsize::new(0);
+
+    let result = scheduler()
+        .send(1, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(RequestError::RetryAfter(Seconds::from_seconds(0))),
+                _ => Ok(42),
+            }
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_repeated_retry_after_gives_up() {
+    let calls = AtomicUsize::new(0);
+
+    let result: Result<(), _> = scheduler()
+        .send(1, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(RequestError::RetryAfter(Seconds::from_seconds(0)))
+        })
+        .await;
+
+    assert!(matches!(result, Err(RequestError::RetryAfter(_))));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_other_errors_are_not_retried() {
+    let calls = AtomicUsize::new(0);
+
+    let result: Result<(), _> = scheduler()
+        .send(1, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(RequestError::MigrateToChatId(teloxide::types::ChatId(2)))
+        })
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_unreachable_errors() {
+    for error in [
+        teloxide::ApiError::BotBlocked,
+        teloxide::ApiError::ChatNotFound,
+        teloxide::ApiError::UserDeactivated,
+    ] {
+        assert!(is_unreachable(&RequestError::Api(error)));
+    }
+
+    assert!(!is_unreachable(&RequestError::Api(
+        teloxide::ApiError::MessageIsTooLong
+    )));
+    assert!(!is_unreachable(&RequestError::RetryAfter(
+        Seconds::from_seconds(5)
+    )));
+}
@@ -0,0 +1,62 @@
+use teloxide::types::{InlineKeyboardButtonKind, InlineKeyboardMarkup, MessageId};
+use tgfeed_common::search::SearchQuery;
+
+use crate::search::{PAGE_SIZE, Searches, keyboard, parse_callback};
+
+fn buttons(markup: InlineKeyboardMarkup) -> Vec<(String, String)> {
+    markup.inline_keyboard[0]
+        .iter()
+        .map(|button| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => (button.text.clone(), data.clone()),
+            _ => panic!("not a callback button"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_keyboard_single_page() {
+    assert!(keyboard(0, 0).is_none());
+    assert!(keyboard(0, PAGE_SIZE as u64).is_none());
+}
+
+#[test]
+fn test_keyboard_buttons() {
+    let total = 3 * PAGE_SIZE as u64;
+
+    let first = buttons(keyboard(0, total).unwrap());
+    assert_eq!(first, vec![
+        ("1/3".to_string(), "search:0".to_string()),
+        ("▶️".to_string(), "search:1".to_string()),
+    ]);
+
+    let middle = buttons(keyboard(1, total).unwrap());
+    assert_eq!(middle.len(), 3);
+    assert_eq!(middle[0].1, "search:0");
+    assert_eq!(middle[2].1, "search:2");
+
+    let last = buttons(keyboard(2, total).unwrap());
+    assert_eq!(last, vec![
+        ("◀️".to_string(), "search:1".to_string()),
+        ("3/3".to_string(), "search:2".to_string()),
+    ]);
+}
+
+#[test]
+fn test_parse_callback() {
+    assert_eq!(parse_callback("search:4"), Some(4));
+    assert_eq!(parse_callback("search:-1"), None);
+    assert_eq!(parse_callback("other:1"), None);
+}
+
+#[test]
+fn test_searches_keep_latest_per_user() {
+    let searches = Searches::default();
+    let query = |text: &str| text.parse::<SearchQuery>().unwrap();
+
+    searches.start(1, MessageId(10), query("first"));
+    searches.start(1, MessageId(11), query("second"));
+
+    assert_eq!(searches.get(1, MessageId(10)), None);
+    assert_eq!(searches.get(1, MessageId(11)), Some(query("second")));
+    assert_eq!(searches.get(2, MessageId(11)), None);
+}
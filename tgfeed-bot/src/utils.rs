@@ -1,3 +1,5 @@
+use tgfeed_common::utils::post_url;
+
 pub(crate) const TELEGRAM_MAX_LENGTH: usize = 4096;
 
 pub fn format_message(
@@ -11,7 +13,7 @@ pub fn format_message(
 
     let channel_part = format!("📢 @{channel_handle}");
     let separator = "──────────";
-    let source_link = format!("https://t.me/c/{channel_id}/{message_id}");
+    let source_link = post_url(channel_id, message_id);
 
     let full_text = format!("{channel_part}\n{separator}\n{text}\n{separator}\nSource",);
 
@@ -1,4 +1,27 @@
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::filter::{Filter, FilterChange};
+use crate::schedule::Schedule;
+use crate::search::{SearchQuery, SearchResults};
+
+/// Previously generated summary
+#[derive(Debug, Clone)]
+pub struct SummaryEntry {
+    pub since: chrono::DateTime<chrono::Utc>,
+    pub until: chrono::DateTime<chrono::Utc>,
+    pub channels: Vec<String>,
+    pub model: String,
+    pub html: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Posts dropped as ads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedAds {
+    pub channel: String,
+    pub rule: String,
+    pub count: u64,
+}
 
 #[derive(Debug)]
 pub enum MonitorCommand {
@@ -21,9 +44,58 @@ pub enum MonitorCommand {
 
     Summarize {
         user_id: i64,
+        /// Receives the summary text as it is generated
+        progress: Option<mpsc::UnboundedSender<String>>,
         response: oneshot::Sender<Result<String, String>>,
     },
 
+    /// Answer a question from the user's recent feed
+    Ask {
+        user_id: i64,
+        question: String,
+        response: oneshot::Sender<Result<String, String>>,
+    },
+
+    /// Posts of the user's channels matching a query, most relevant first
+    Search {
+        user_id: i64,
+        query: SearchQuery,
+        offset: u64,
+        limit: usize,
+        response: oneshot::Sender<Result<SearchResults, String>>,
+    },
+
+    /// Stored summaries, newest first
+    SummaryHistory {
+        user_id: i64,
+        limit: usize,
+        response: oneshot::Sender<Result<Vec<SummaryEntry>, String>>,
+    },
+
+    /// Set the automatic digest schedule, `None` turns digests off
+    SetDigest {
+        user_id: i64,
+        schedule: Option<Schedule>,
+        response: oneshot::Sender<Result<(), String>>,
+    },
+
+    GetDigest {
+        user_id: i64,
+        response: oneshot::Sender<Result<Option<Schedule>, String>>,
+    },
+
+    /// Apply `change` to the user's filter rules, then list them oldest first
+    Filters {
+        user_id: i64,
+        change: Option<FilterChange>,
+        response: oneshot::Sender<Result<Vec<Filter>, String>>,
+    },
+
+    /// Posts dropped as ads since startup, by channel and rule
+    DroppedAds {
+        response: oneshot::Sender<Result<Vec<DroppedAds>, String>>,
+    },
+
     Shutdown,
 }
 
@@ -33,8 +105,14 @@ impl MonitorCommand {
             MonitorCommand::Subscribe { user_id, .. }
             | MonitorCommand::Unsubscribe { user_id, .. }
             | MonitorCommand::ListSubscriptions { user_id, .. }
-            | MonitorCommand::Summarize { user_id, .. } => Some(*user_id),
-            MonitorCommand::Shutdown => None,
+            | MonitorCommand::Summarize { user_id, .. }
+            | MonitorCommand::Ask { user_id, .. }
+            | MonitorCommand::Search { user_id, .. }
+            | MonitorCommand::SummaryHistory { user_id, .. }
+            | MonitorCommand::SetDigest { user_id, .. }
+            | MonitorCommand::GetDigest { user_id, .. }
+            | MonitorCommand::Filters { user_id, .. } => Some(*user_id),
+            MonitorCommand::DroppedAds { .. } | MonitorCommand::Shutdown => None,
         }
     }
 
@@ -52,6 +130,27 @@ impl MonitorCommand {
             MonitorCommand::Summarize { response, .. } => {
                 response.send(Err(message)).expect("broken channel")
             }
+            MonitorCommand::Ask { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::Search { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::SummaryHistory { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::SetDigest { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::GetDigest { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::Filters { response, .. } => {
+                response.send(Err(message)).expect("broken channel")
+            }
+            MonitorCommand::DroppedAds { response } => {
+                response.send(Err(message)).expect("broken channel")
+            }
             MonitorCommand::Shutdown => (),
         }
     }
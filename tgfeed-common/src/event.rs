@@ -1,3 +1,4 @@
+#[derive(serde::Serialize, serde::Deserialize)]
 pub enum BotEvent {
     NewMessage {
         channel_id: i64,
@@ -7,4 +8,22 @@ pub enum BotEvent {
         subscribers: Vec<i64>,
         entities: Vec<teloxide::types::MessageEntity>,
     },
+
+    /// Channel edited a post, copies delivered to users get the new text
+    PostEdited {
+        channel_id: i64,
+        channel_handle: String,
+        message_id: i32,
+        text: String,
+        entities: Vec<teloxide::types::MessageEntity>,
+    },
+
+    /// Channel deleted posts, copies delivered to users are marked
+    PostsDeleted {
+        channel_id: i64,
+        message_ids: Vec<i32>,
+    },
+
+    /// Scheduled summary for a single user
+    Digest { user_id: i64, summary: String },
 }
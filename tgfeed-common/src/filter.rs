@@ -0,0 +1,188 @@
+//! Per-user rules deciding which forwarded posts reach the user.
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+/// Compiled size cap, keeps user regexes cheap to match on every post
+const MAX_REGEX_SIZE: usize = 1 << 16;
+const MAX_PATTERN_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Forward only posts matching some include rule
+    Include,
+    /// Never forward matching posts
+    Exclude,
+}
+
+/// Text a rule looks for, matched case-insensitively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Keyword(String),
+    Regex(String),
+}
+
+impl Pattern {
+    fn compile(&self) -> Result<Regex, regex::Error> {
+        let source = match self {
+            Self::Keyword(keyword) => regex::escape(keyword),
+            Self::Regex(regex) => regex.clone(),
+        };
+
+        RegexBuilder::new(&source)
+            .case_insensitive(true)
+            .size_limit(MAX_REGEX_SIZE)
+            .build()
+    }
+}
+
+/// `/filter` rule, limited to one channel when `channel` is set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub action: FilterAction,
+    /// Channel handle without `@`
+    pub channel: Option<String>,
+    pub pattern: Pattern,
+}
+
+/// Accepts `include|exclude [@channel] keywords` and
+/// `include|exclude [@channel] /regex/`
+impl FromStr for Filter {
+    type Err = InvalidFilter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (action, rest) = s.split_once(char::is_whitespace).ok_or(InvalidFilter)?;
+
+        let action = match action {
+            "include" => FilterAction::Include,
+            "exclude" => FilterAction::Exclude,
+            _ => return Err(InvalidFilter),
+        };
+
+        let mut rest = rest.trim_start();
+        let mut channel = None;
+        if let Some(handle) = rest.strip_prefix('@') {
+            let (handle, tail) = handle
+                .split_once(char::is_whitespace)
+                .ok_or(InvalidFilter)?;
+            if handle.is_empty() {
+                return Err(InvalidFilter);
+            }
+            channel = Some(handle.to_string());
+            rest = tail.trim_start();
+        }
+
+        let pattern = match rest.strip_prefix('/').and_then(|r| r.strip_suffix('/')) {
+            Some(regex) => Pattern::Regex(regex.to_string()),
+            None => Pattern::Keyword(rest.split_whitespace().collect::<Vec<_>>().join(" ")),
+        };
+
+        let (Pattern::Keyword(text) | Pattern::Regex(text)) = &pattern;
+        if text.is_empty() || text.chars().count() > MAX_PATTERN_LENGTH {
+            return Err(InvalidFilter);
+        }
+        pattern.compile().map_err(|_| InvalidFilter)?;
+
+        Ok(Self {
+            action,
+            channel,
+            pattern,
+        })
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            FilterAction::Include => write!(f, "include")?,
+            FilterAction::Exclude => write!(f, "exclude")?,
+        }
+
+        if let Some(channel) = &self.channel {
+            write!(f, " @{channel}")?;
+        }
+
+        match &self.pattern {
+            Pattern::Keyword(keyword) => write!(f, " {keyword}"),
+            Pattern::Regex(regex) => write!(f, " /{regex}/"),
+        }
+    }
+}
+
+/// Edit of a user's filter list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterChange {
+    Add(Filter),
+    /// 1-based position in the listed rules
+    Remove(usize),
+    Clear,
+}
+
+/// Accepts a [`Filter`], `remove N` and `clear`
+impl FromStr for FilterChange {
+    type Err = InvalidFilter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("clear"), None, None) => Ok(Self::Clear),
+            (Some("remove"), Some(position), None) => match position.parse() {
+                Ok(position) if position > 0 => Ok(Self::Remove(position)),
+                _ => Err(InvalidFilter),
+            },
+            _ => s.parse().map(Self::Add),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidFilter;
+
+impl fmt::Display for InvalidFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected include or exclude, optionally @channel, then keywords or a /regex/ of up to {MAX_PATTERN_LENGTH} characters"
+        )
+    }
+}
+
+impl std::error::Error for InvalidFilter {}
+
+/// Compiled rules of one user for one channel
+#[derive(Debug, Default)]
+pub struct FilterSet {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl FilterSet {
+    /// Rules that fail to compile are skipped
+    pub fn new(rules: impl IntoIterator<Item = (FilterAction, Pattern)>) -> Self {
+        let mut set = Self::default();
+
+        for (action, pattern) in rules {
+            let Ok(regex) = pattern.compile() else {
+                continue;
+            };
+
+            match action {
+                FilterAction::Include => set.include.push(regex),
+                FilterAction::Exclude => set.exclude.push(regex),
+            }
+        }
+
+        set
+    }
+
+    /// Whether a post with `text` is forwarded: no exclude rule matches and,
+    /// when there are include rules, at least one of them does
+    pub fn allows(&self, text: &str) -> bool {
+        !self.exclude.iter().any(|regex| regex.is_match(text))
+            && (self.include.is_empty() || self.include.iter().any(|regex| regex.is_match(text)))
+    }
+}
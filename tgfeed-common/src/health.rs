@@ -0,0 +1,26 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag telling whether a long-running task is still alive
+#[derive(Clone, Default)]
+pub struct Liveness(Arc<AtomicBool>);
+
+impl Liveness {
+    pub fn is_alive(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Mark the task as alive until the returned guard is dropped
+    pub fn guard(&self) -> LivenessGuard {
+        self.0.store(true, Ordering::Release);
+        LivenessGuard(Arc::clone(&self.0))
+    }
+}
+
+pub struct LivenessGuard(Arc<AtomicBool>);
+
+impl Drop for LivenessGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
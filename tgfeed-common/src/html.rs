@@ -0,0 +1,451 @@
+//! Telegram-safe HTML for model output.
+//!
+//! Telegram rejects the whole message over a single unsupported or
+//! unbalanced tag, or a stray `<`, `>` or `&`, see
+//! <https://core.telegram.org/bots/api#html-style>.
+
+/// Tags understood by Telegram
+const TAGS: &[&str] = &[
+    "a",
+    "b",
+    "blockquote",
+    "code",
+    "del",
+    "em",
+    "i",
+    "ins",
+    "pre",
+    "s",
+    "span",
+    "strike",
+    "strong",
+    "tg-emoji",
+    "tg-spoiler",
+    "u",
+];
+
+enum Token {
+    /// Unescaped text
+    Text(String),
+    Open {
+        name: &'static str,
+        /// Rendered attributes, with a leading space
+        attrs: String,
+    },
+    Close(&'static str),
+}
+
+/// Keep supported tags with their supported attributes, balance them and
+/// escape everything else
+pub fn sanitize_html(text: &str) -> String {
+    let mut html = String::new();
+
+    for token in parse(text) {
+        render(&token, &mut html);
+    }
+
+    html
+}
+
+/// Sanitize and split into parts of at most `max_length` visible UTF-16
+/// units, closing tags open at the end of a part and reopening them in the
+/// next. Prefers splitting at a newline in the second half of a part
+pub fn split_html(text: &str, max_length: usize) -> Vec<String> {
+    let mut splitter = Splitter {
+        max_length,
+        parts: Vec::new(),
+        part: String::new(),
+        length: 0,
+        has_content: false,
+        open: Vec::new(),
+    };
+
+    for token in parse(text) {
+        splitter.push(token);
+    }
+
+    splitter.finish()
+}
+
+struct Splitter {
+    max_length: usize,
+    parts: Vec<String>,
+    part: String,
+    /// Visible length of `part`
+    length: usize,
+    /// Whether `part` has anything but whitespace, Telegram rejects empty
+    /// messages
+    has_content: bool,
+    open: Vec<(&'static str, String)>,
+}
+
+impl Splitter {
+    fn push(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                let mut text = text.as_str();
+
+                while !text.is_empty() {
+                    let (head, tail) = self.cut(text);
+
+                    self.part.push_str(&escape(head));
+                    self.length += head.encode_utf16().count();
+                    self.has_content |= !head.trim().is_empty();
+
+                    text = tail;
+                    if !text.is_empty() {
+                        self.flush();
+                    }
+                }
+            }
+            Token::Open { name, attrs } => {
+                render(
+                    &Token::Open {
+                        name,
+                        attrs: attrs.clone(),
+                    },
+                    &mut self.part,
+                );
+                self.open.push((name, attrs));
+            }
+            Token::Close(name) => {
+                render(&Token::Close(name), &mut self.part);
+                self.open.pop();
+            }
+        }
+    }
+
+    /// Longest head of `text` that fits into the current part
+    fn cut<'a>(&self, text: &'a str) -> (&'a str, &'a str) {
+        let room = self.max_length.saturating_sub(self.length);
+
+        let mut units = 0;
+        let mut end = text.len();
+        for (i, c) in text.char_indices() {
+            if units + c.len_utf16() > room {
+                end = i;
+                break;
+            }
+            units += c.len_utf16();
+        }
+
+        if end == text.len() {
+            return (text, "");
+        }
+
+        // An empty part can't make room, so it takes at least one character
+        if end == 0 && self.length == 0 {
+            end = text.chars().next().map_or(0, char::len_utf8);
+        }
+
+        let head = &text[..end];
+        if let Some(newline) = head.rfind('\n') {
+            let split = newline + 1;
+            if self.length + head[..split].encode_utf16().count() >= self.max_length / 2 {
+                return text.split_at(split);
+            }
+        }
+
+        text.split_at(end)
+    }
+
+    fn flush(&mut self) {
+        for (name, _) in self.open.iter().rev() {
+            render(&Token::Close(name), &mut self.part);
+        }
+
+        let part = std::mem::take(&mut self.part);
+        if self.has_content {
+            self.parts.push(part);
+        }
+
+        for (name, attrs) in &self.open {
+            render(
+                &Token::Open {
+                    name,
+                    attrs: attrs.clone(),
+                },
+                &mut self.part,
+            );
+        }
+
+        self.length = 0;
+        self.has_content = false;
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if self.has_content {
+            self.parts.push(self.part);
+        }
+
+        self.parts
+    }
+}
+
+fn parse(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut open: Vec<&'static str> = Vec::new();
+    let mut plain = String::new();
+
+    let flush = |plain: &mut String, tokens: &mut Vec<Token>| {
+        if !plain.is_empty() {
+            tokens.push(Token::Text(std::mem::take(plain)));
+        }
+    };
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some((tag, len)) = parse_tag(rest)
+        {
+            rest = &rest[len..];
+
+            let known = TAGS.iter().copied().find(|t| *t == tag.name);
+
+            match (known, tag.closing) {
+                (Some(name), true) => {
+                    if let Some(position) = open.iter().rposition(|t| *t == name) {
+                        flush(&mut plain, &mut tokens);
+                        // Close whatever the model left open inside
+                        for name in open.drain(position..).rev() {
+                            tokens.push(Token::Close(name));
+                        }
+                    }
+                }
+                (Some(name), false) => {
+                    if let Some(attrs) = attributes(name, tag.attrs)
+                        && can_open(&open, name)
+                    {
+                        flush(&mut plain, &mut tokens);
+                        tokens.push(Token::Open { name, attrs });
+                        open.push(name);
+                    }
+                }
+                // Layout tags are replaced with line breaks, anything else
+                // unsupported is dropped
+                (None, closing) => match (tag.name.as_str(), closing) {
+                    ("br", false) | ("p" | "div", true) => plain.push('\n'),
+                    _ => (),
+                },
+            }
+
+            continue;
+        }
+
+        if c == '&'
+            && let Some((decoded, len)) = parse_entity(rest)
+        {
+            plain.push(decoded);
+            rest = &rest[len..];
+            continue;
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    flush(&mut plain, &mut tokens);
+    for name in open.into_iter().rev() {
+        tokens.push(Token::Close(name));
+    }
+
+    tokens
+}
+
+/// Telegram allows no formatting inside code blocks, except `<code>`
+/// setting the language of a `<pre>`
+fn can_open(open: &[&str], name: &str) -> bool {
+    match open.last() {
+        Some(&"pre") => name == "code",
+        _ => !open.contains(&"code") && !open.contains(&"pre"),
+    }
+}
+
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    attrs: &'a str,
+}
+
+/// Tag at the start of `text` along with its length, `None` for a stray `<`
+fn parse_tag(text: &str) -> Option<(Tag<'_>, usize)> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+
+    if inner.contains('<') {
+        return None;
+    }
+
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let inner = inner.strip_suffix('/').unwrap_or(inner);
+
+    let name_end = inner
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(inner.len());
+    let (name, attrs) = inner.split_at(name_end);
+
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !(attrs.is_empty() || attrs.starts_with(char::is_whitespace))
+        || (closing && !attrs.trim().is_empty())
+    {
+        return None;
+    }
+
+    let tag = Tag {
+        name: name.to_ascii_lowercase(),
+        closing,
+        attrs,
+    };
+
+    Some((tag, end + 1))
+}
+
+/// Supported attributes of `name`, or `None` if the tag is unusable without
+/// them
+fn attributes(name: &str, raw: &str) -> Option<String> {
+    let attrs = parse_attributes(raw);
+    let get = |key: &str| {
+        attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    match name {
+        "a" => get("href")
+            .filter(|href| !href.is_empty())
+            .map(|href| format!(" href=\"{}\"", escape(href))),
+        "span" => (get("class") == Some("tg-spoiler")).then(|| " class=\"tg-spoiler\"".to_string()),
+        "tg-emoji" => get("emoji-id")
+            .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+            .map(|id| format!(" emoji-id=\"{id}\"")),
+        "code" => Some(
+            get("class")
+                .filter(|class| class.starts_with("language-"))
+                .map(|class| format!(" class=\"{}\"", escape(class)))
+                .unwrap_or_default(),
+        ),
+        "blockquote" if get("expandable").is_some() => Some(" expandable".to_string()),
+        _ => Some(String::new()),
+    }
+}
+
+/// `key="value"`, `key='value'`, `key=value` and bare `key` attributes with
+/// entities in values decoded
+fn parse_attributes(raw: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = raw.trim_start();
+
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                let (value, tail) = match value.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let value = &value[1..];
+                        let end = value.find(quote).unwrap_or(value.len());
+                        (&value[..end], value.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        value.split_at(end)
+                    }
+                };
+                rest = tail.trim_start();
+                decode(value)
+            }
+            None => String::new(),
+        };
+
+        attrs.push((key, value));
+    }
+
+    attrs
+}
+
+/// Entity at the start of `text`, which begins with `&`, and its length
+fn parse_entity(text: &str) -> Option<(char, usize)> {
+    let name = text.strip_prefix('&')?;
+    let end = name.find(';').filter(|&end| (1..=10).contains(&end))?;
+
+    let decoded = match &name[..end] {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        entity => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+
+    Some((decoded, end + 2))
+}
+
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let entity = if c == '&' { parse_entity(rest) } else { None };
+
+        match entity {
+            Some((entity, len)) => {
+                decoded.push(entity);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    decoded
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn render(token: &Token, html: &mut String) {
+    match token {
+        Token::Text(text) => html.push_str(&escape(text)),
+        Token::Open { name, attrs } => {
+            html.push('<');
+            html.push_str(name);
+            html.push_str(attrs);
+            html.push('>');
+        }
+        Token::Close(name) => {
+            html.push_str("</");
+            html.push_str(name);
+            html.push('>');
+        }
+    }
+}
@@ -1,5 +1,10 @@
 pub mod command;
 pub mod event;
+pub mod filter;
+pub mod health;
+pub mod html;
+pub mod schedule;
+pub mod search;
 pub mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,155 @@
+//! Recurring digest schedules.
+//!
+//! Daily digests use a fixed UTC offset rather than a named time zone, so
+//! they don't follow daylight saving changes.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+
+const MAX_EVERY_HOURS: u32 = 7 * 24;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// When automatic digests are delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Every day at a local time
+    Daily {
+        minute_of_day: u32,
+        utc_offset_minutes: i32,
+    },
+    /// Every `hours` hours
+    Every { hours: u32 },
+}
+
+impl Schedule {
+    /// First delivery time strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            Self::Every { hours } => after + Duration::hours(hours.into()),
+            Self::Daily {
+                minute_of_day,
+                utc_offset_minutes,
+            } => {
+                let offset = Duration::minutes(utc_offset_minutes.into());
+                let local = after + offset;
+
+                let mut next = local.date_naive().and_time(Default::default()).and_utc()
+                    + Duration::minutes(minute_of_day.into());
+                if next <= local {
+                    next += Duration::days(1);
+                }
+
+                next - offset
+            }
+        }
+    }
+}
+
+/// Accepts `daily HH:MM [±HH[:MM]]` and `every N[h]`
+impl FromStr for Schedule {
+    type Err = InvalidSchedule;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+
+        let schedule = match (parts.next(), parts.next(), parts.next()) {
+            (Some("daily"), Some(time), offset) => Self::Daily {
+                minute_of_day: parse_time(time).ok_or(InvalidSchedule)?,
+                utc_offset_minutes: offset
+                    .map_or(Some(0), parse_offset)
+                    .ok_or(InvalidSchedule)?,
+            },
+            (Some("every"), Some(hours), None) => {
+                let hours = parse_digits(hours.trim_end_matches('h')).ok_or(InvalidSchedule)?;
+
+                if !(1..=MAX_EVERY_HOURS).contains(&hours) {
+                    return Err(InvalidSchedule);
+                }
+
+                Self::Every { hours }
+            }
+            _ => return Err(InvalidSchedule),
+        };
+
+        if parts.next().is_some() {
+            return Err(InvalidSchedule);
+        }
+
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Daily {
+                minute_of_day,
+                utc_offset_minutes,
+            } => {
+                let sign = if utc_offset_minutes < 0 { '-' } else { '+' };
+                let offset = utc_offset_minutes.unsigned_abs();
+
+                write!(
+                    f,
+                    "daily at {:02}:{:02} (UTC{sign}{:02}:{:02})",
+                    minute_of_day / 60,
+                    minute_of_day % 60,
+                    offset / 60,
+                    offset % 60,
+                )
+            }
+            Self::Every { hours: 1 } => write!(f, "every hour"),
+            Self::Every { hours } => write!(f, "every {hours} hours"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidSchedule;
+
+impl fmt::Display for InvalidSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected `daily HH:MM [+HH:MM]` or `every N` hours (1-{MAX_EVERY_HOURS})"
+        )
+    }
+}
+
+impl std::error::Error for InvalidSchedule {}
+
+/// `HH:MM` as minutes after midnight
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    let (hours, minutes) = (parse_digits(hours)?, parse_digits(minutes)?);
+
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// `±HH[:MM]`, optionally prefixed with `UTC`, as minutes
+fn parse_offset(offset: &str) -> Option<i32> {
+    let offset = offset.strip_prefix("UTC").unwrap_or(offset);
+
+    let (sign, offset) = match offset.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let (hours, minutes) = (parse_digits(hours)?, parse_digits(minutes)?);
+
+    let total = i32::try_from(hours.checked_mul(60)?.checked_add(minutes)?).ok()?;
+    (minutes < 60 && total <= MAX_UTC_OFFSET_MINUTES).then_some(sign * total)
+}
+
+/// Unsigned number written with ASCII digits only, no sign
+fn parse_digits(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    text.parse().ok()
+}
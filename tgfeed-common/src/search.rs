@@ -0,0 +1,88 @@
+//! Full-text search queries and results.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Oldest posts are pruned by retention anyway
+const MAX_DAYS: u32 = 365;
+
+/// `/search` query: words to find, narrowed by `@channel` tokens to some of
+/// the user's channels and by an `Nd` token to the last N days
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Channel handles without `@`
+    pub channels: Vec<String>,
+    pub days: Option<u32>,
+}
+
+impl FromStr for SearchQuery {
+    type Err = InvalidSearch;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Vec::new();
+        let mut channels = Vec::new();
+        let mut days = None;
+
+        for token in s.split_whitespace() {
+            if let Some(handle) = token.strip_prefix('@') {
+                if handle.is_empty() {
+                    return Err(InvalidSearch);
+                }
+                channels.push(handle.to_string());
+            } else if let Some(count) = token
+                .strip_suffix('d')
+                .and_then(|count| count.parse::<u32>().ok())
+            {
+                if !(1..=MAX_DAYS).contains(&count) || days.is_some() {
+                    return Err(InvalidSearch);
+                }
+                days = Some(count);
+            } else {
+                words.push(token);
+            }
+        }
+
+        if words.is_empty() {
+            return Err(InvalidSearch);
+        }
+
+        Ok(Self {
+            text: words.join(" "),
+            channels,
+            days,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidSearch;
+
+impl fmt::Display for InvalidSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected words to find, optionally with @channel and a number of days (1-{MAX_DAYS}) like 7d"
+        )
+    }
+}
+
+impl std::error::Error for InvalidSearch {}
+
+/// Stored post matching a search
+#[derive(Debug, Clone)]
+pub struct FoundPost {
+    pub channel_id: i64,
+    pub channel_handle: String,
+    pub message_id: i32,
+    pub text: String,
+    pub date: chrono::DateTime<chrono::Utc>,
+}
+
+/// Single page of search results
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    pub posts: Vec<FoundPost>,
+    /// Matches across all pages
+    pub total: u64,
+}
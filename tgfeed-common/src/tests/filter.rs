@@ -0,0 +1,108 @@
+use crate::filter::{Filter, FilterAction, FilterChange, FilterSet, InvalidFilter, Pattern};
+
+fn set(rules: &[&str]) -> FilterSet {
+    FilterSet::new(rules.iter().map(|rule| {
+        let filter = rule.parse::<Filter>().unwrap();
+        (filter.action, filter.pattern)
+    }))
+}
+
+#[test]
+fn test_parse_keyword_filter() {
+    assert_eq!(
+        "include  central   bank ".parse(),
+        Ok(Filter {
+            action: FilterAction::Include,
+            channel: None,
+            pattern: Pattern::Keyword("central bank".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_parse_channel_regex_filter() {
+    assert_eq!(
+        "exclude @news /#(ad|promo)\\b/".parse(),
+        Ok(Filter {
+            action: FilterAction::Exclude,
+            channel: Some("news".to_string()),
+            pattern: Pattern::Regex("#(ad|promo)\\b".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_parse_invalid_filters() {
+    for filter in [
+        "",
+        "include",
+        "include @news",
+        "include @ rates",
+        "block rates",
+        "exclude /(unclosed/",
+        "exclude //",
+    ] {
+        assert_eq!(filter.parse::<Filter>(), Err(InvalidFilter), "{filter:?}");
+    }
+
+    let long = format!("include {}", "a".repeat(201));
+    assert_eq!(long.parse::<Filter>(), Err(InvalidFilter));
+}
+
+#[test]
+fn test_display_round_trips() {
+    for filter in [
+        "include rates",
+        "exclude @news /^ad/",
+        "include @economy key rate",
+    ] {
+        assert_eq!(filter.parse::<Filter>().unwrap().to_string(), filter);
+    }
+}
+
+#[test]
+fn test_parse_changes() {
+    assert_eq!("clear".parse(), Ok(FilterChange::Clear));
+    assert_eq!(" remove 2 ".parse(), Ok(FilterChange::Remove(2)));
+    assert!(matches!(
+        "exclude ads".parse(),
+        Ok(FilterChange::Add(Filter {
+            action: FilterAction::Exclude,
+            ..
+        }))
+    ));
+
+    for change in ["remove", "remove 0", "remove x", "clear all"] {
+        assert_eq!(
+            change.parse::<FilterChange>(),
+            Err(InvalidFilter),
+            "{change:?}"
+        );
+    }
+}
+
+#[test]
+fn test_empty_set_allows_everything() {
+    assert!(set(&[]).allows("anything"));
+}
+
+#[test]
+fn test_keywords_match_case_insensitively() {
+    let filters = set(&["include Bank", "include a.b"]);
+
+    assert!(filters.allows("Central BANK raises rates"));
+    assert!(filters.allows("see a.b"));
+    assert!(!filters.allows("see axb"));
+    assert!(!filters.allows("weather"));
+}
+
+#[test]
+fn test_exclude_wins_over_include() {
+    let filters = set(&["include rates", "exclude /#ad\\b/"]);
+
+    assert!(filters.allows("Rates are up"));
+    assert!(!filters.allows("Best rates here #AD"));
+    assert!(filters.allows("Best rates here #adventure"));
+    assert!(!set(&["exclude promo"]).allows("PROMO code"));
+    assert!(set(&["exclude promo"]).allows("news"));
+}
@@ -0,0 +1,19 @@
+use crate::health::Liveness;
+
+#[test]
+fn test_liveness_default_not_alive() {
+    let liveness = Liveness::default();
+    assert!(!liveness.is_alive());
+}
+
+#[test]
+fn test_liveness_guard_lifetime() {
+    let liveness = Liveness::default();
+
+    let guard = liveness.guard();
+    assert!(liveness.is_alive());
+    assert!(liveness.clone().is_alive());
+
+    drop(guard);
+    assert!(!liveness.is_alive());
+}
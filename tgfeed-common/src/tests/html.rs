@@ -0,0 +1,128 @@
+use crate::html::{sanitize_html, split_html};
+
+#[test]
+fn test_sanitize_keeps_supported_tags() {
+    let html = "<b>bold</b> <i>it</i> <a href=\"https://t.me/c/1/2\">link</a> \
+                <span class=\"tg-spoiler\">hidden</span> <blockquote expandable>q</blockquote>";
+
+    assert_eq!(sanitize_html(html), html);
+}
+
+#[test]
+fn test_sanitize_escapes_stray_characters() {
+    assert_eq!(
+        sanitize_html("1 < 2 & 3 > 2, <3"),
+        "1 &lt; 2 &amp; 3 &gt; 2, &lt;3"
+    );
+}
+
+#[test]
+fn test_sanitize_keeps_valid_entities() {
+    assert_eq!(
+        sanitize_html("&lt;b&gt; &amp; &#128512; &nbsp;"),
+        "&lt;b&gt; &amp; 😀 &amp;nbsp;"
+    );
+}
+
+#[test]
+fn test_sanitize_closes_unclosed_tags() {
+    assert_eq!(sanitize_html("<b>bold <i>both"), "<b>bold <i>both</i></b>");
+    assert_eq!(
+        sanitize_html("<b>bold <i>both</b> plain"),
+        "<b>bold <i>both</i></b> plain"
+    );
+}
+
+#[test]
+fn test_sanitize_drops_unmatched_closing_tags() {
+    assert_eq!(sanitize_html("plain</b> text</i>"), "plain text");
+}
+
+#[test]
+fn test_sanitize_drops_unsupported_tags() {
+    assert_eq!(
+        sanitize_html("<h1>Title</h1><p>one</p><p>two<br>three</p><ul><li>x</li></ul>"),
+        "Titleone\ntwo\nthree\nx"
+    );
+}
+
+#[test]
+fn test_sanitize_filters_attributes() {
+    assert_eq!(
+        sanitize_html("<b class=\"x\">b</b> <a>no href</a> <span style=\"x\">s</span>"),
+        "<b>b</b> no href s"
+    );
+    assert_eq!(
+        sanitize_html("<a href='https://x.com/?a=1&amp;b=2' onclick=\"x\">l</a>"),
+        "<a href=\"https://x.com/?a=1&amp;b=2\">l</a>"
+    );
+}
+
+#[test]
+fn test_sanitize_semicolons_outside_entities() {
+    assert_eq!(
+        sanitize_html("<a href=\"https://x.com/?a=1;b=2\">l</a>"),
+        "<a href=\"https://x.com/?a=1;b=2\">l</a>"
+    );
+    assert_eq!(sanitize_html("жж; &;ж; &ж;"), "жж; &amp;;ж; &amp;ж;");
+}
+
+#[test]
+fn test_sanitize_no_formatting_inside_code() {
+    assert_eq!(
+        sanitize_html("<code><b>x</b></code> <pre><code class=\"language-rust\">y</code></pre>"),
+        "<code>x</code> <pre><code class=\"language-rust\">y</code></pre>"
+    );
+}
+
+#[test]
+fn test_split_short_is_single_part() {
+    assert_eq!(split_html("<b>short</b>", 100), vec!["<b>short</b>"]);
+}
+
+#[test]
+fn test_split_closes_and_reopens_tags() {
+    let parts = split_html("<b>aaaa <i>bbbb</i> cccc</b>", 8);
+
+    assert_eq!(parts, vec![
+        "<b>aaaa <i>bbb</i></b>",
+        "<b><i>b</i> cccc</b>",
+    ]);
+}
+
+#[test]
+fn test_split_keeps_attributes_on_reopen() {
+    let parts = split_html("<a href=\"https://t.me\">abcdef</a>", 3);
+
+    assert_eq!(parts, vec![
+        "<a href=\"https://t.me\">abc</a>",
+        "<a href=\"https://t.me\">def</a>",
+    ]);
+}
+
+#[test]
+fn test_split_counts_entities_as_one_character() {
+    let parts = split_html("&lt;&lt;&lt;&lt;", 2);
+
+    assert_eq!(parts, vec!["&lt;&lt;", "&lt;&lt;"]);
+}
+
+#[test]
+fn test_split_prefers_newline() {
+    let parts = split_html("<b>first line\nsecond</b>", 15);
+
+    assert_eq!(parts, vec!["<b>first line\n</b>", "<b>second</b>"]);
+}
+
+#[test]
+fn test_split_counts_utf16() {
+    let parts = split_html("😀😀😀", 4);
+
+    assert_eq!(parts, vec!["😀😀", "😀"]);
+}
+
+#[test]
+fn test_split_skips_empty_parts() {
+    assert!(split_html("<b></b>", 10).is_empty());
+    assert_eq!(split_html("<b>abcd</b>\n", 4), vec!["<b>abcd</b>"]);
+}
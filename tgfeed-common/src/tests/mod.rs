@@ -1 +1,6 @@
+mod filter;
+mod health;
+mod html;
 mod message_entity;
+mod schedule;
+mod search;
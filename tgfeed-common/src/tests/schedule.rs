@@ -0,0 +1,108 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::schedule::{InvalidSchedule, Schedule};
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 10, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_parse_schedule() {
+    assert_eq!(
+        "daily 09:30".parse(),
+        Ok(Schedule::Daily {
+            minute_of_day: 9 * 60 + 30,
+            utc_offset_minutes: 0,
+        })
+    );
+    assert_eq!(
+        "daily 9:00 UTC-05:30".parse(),
+        Ok(Schedule::Daily {
+            minute_of_day: 9 * 60,
+            utc_offset_minutes: -330,
+        })
+    );
+    assert_eq!(
+        "daily 23:59 +3".parse(),
+        Ok(Schedule::Daily {
+            minute_of_day: 23 * 60 + 59,
+            utc_offset_minutes: 180,
+        })
+    );
+    assert_eq!("every 6h".parse(), Ok(Schedule::Every { hours: 6 }));
+    assert_eq!("every 12".parse(), Ok(Schedule::Every { hours: 12 }));
+
+    for invalid in [
+        "",
+        "daily",
+        "daily 24:00",
+        "daily 09:60",
+        "daily 09:00 3",
+        "daily 09:00 +15",
+        "every 0",
+        "every 1000",
+        "every 6 hours",
+        "weekly 09:00",
+        "daily +9:00",
+        "daily 09:+5",
+        "daily 09:00 +-05:30",
+        "daily 09:00 +05:-30",
+        "daily 09:00 ++5",
+        "every +6",
+    ] {
+        assert_eq!(
+            invalid.parse::<Schedule>(),
+            Err(InvalidSchedule),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn test_daily_next_after() {
+    let schedule = Schedule::Daily {
+        minute_of_day: 9 * 60,
+        utc_offset_minutes: 180,
+    };
+
+    // 09:00 at UTC+3 is 06:00 UTC
+    assert_eq!(schedule.next_after(at(5, 0)), at(6, 0));
+    assert_eq!(
+        schedule.next_after(at(6, 0)),
+        at(6, 0) + chrono::Duration::days(1)
+    );
+    assert_eq!(
+        schedule.next_after(at(23, 0)),
+        at(6, 0) + chrono::Duration::days(1)
+    );
+}
+
+#[test]
+fn test_daily_next_after_crosses_local_midnight() {
+    // 01:00 at UTC-5 is 06:00 UTC, while it's still the previous day locally
+    let schedule = Schedule::Daily {
+        minute_of_day: 60,
+        utc_offset_minutes: -300,
+    };
+
+    assert_eq!(schedule.next_after(at(2, 0)), at(6, 0));
+}
+
+#[test]
+fn test_every_next_after() {
+    let schedule = Schedule::Every { hours: 6 };
+
+    assert_eq!(schedule.next_after(at(5, 0)), at(11, 0));
+}
+
+#[test]
+fn test_schedule_display() {
+    let daily = Schedule::Daily {
+        minute_of_day: 9 * 60 + 5,
+        utc_offset_minutes: -330,
+    };
+
+    assert_eq!(daily.to_string(), "daily at 09:05 (UTC-05:30)");
+    assert_eq!(Schedule::Every { hours: 1 }.to_string(), "every hour");
+    assert_eq!(Schedule::Every { hours: 6 }.to_string(), "every 6 hours");
+}
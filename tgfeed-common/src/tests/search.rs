@@ -0,0 +1,48 @@
+use crate::search::{InvalidSearch, SearchQuery};
+
+#[test]
+fn test_parse_plain_query() {
+    assert_eq!(
+        "central bank rates".parse(),
+        Ok(SearchQuery {
+            text: "central bank rates".to_string(),
+            channels: Vec::new(),
+            days: None,
+        })
+    );
+}
+
+#[test]
+fn test_parse_channels_and_days() {
+    assert_eq!(
+        "@news rates 7d @economy  bank".parse(),
+        Ok(SearchQuery {
+            text: "rates bank".to_string(),
+            channels: vec!["news".to_string(), "economy".to_string()],
+            days: Some(7),
+        })
+    );
+}
+
+#[test]
+fn test_parse_number_words_are_text() {
+    assert_eq!(
+        "covid 19 d".parse::<SearchQuery>().unwrap().text,
+        "covid 19 d"
+    );
+}
+
+#[test]
+fn test_parse_invalid_queries() {
+    for query in [
+        "",
+        "   ",
+        "@news 7d",
+        "rates 0d",
+        "rates 1000d",
+        "rates 1d 2d",
+        "@ rates",
+    ] {
+        assert_eq!(query.parse::<SearchQuery>(), Err(InvalidSearch), "{query}");
+    }
+}
@@ -1,6 +1,11 @@
 use grammers_tl_types as tl;
 use teloxide::types::{MessageEntity, MessageEntityKind};
 
+/// Link to a channel post
+pub fn post_url(channel_id: i64, message_id: i32) -> String {
+    format!("https://t.me/c/{channel_id}/{message_id}")
+}
+
 /// Convert grammers MessageEntity to teloxide MessageEntity
 pub fn convert_entities(entities: Option<&Vec<tl::enums::MessageEntity>>) -> Vec<MessageEntity> {
     let Some(entities) = entities else {
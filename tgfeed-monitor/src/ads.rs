@@ -0,0 +1,228 @@
+//! Ad detection applied to channel posts before they are stored or forwarded.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+
+use regex::Regex;
+use tgfeed_common::command::DroppedAds;
+
+// ERID tokens are typically 8+ characters, alphanumeric
+pub(crate) const AD_PATTERN_STR: &str = r"(?i:#реклама|(?:^|[\s\/\\?&])erid[\s:=]+[a-z0-9]{8,})";
+
+const UTM_PATTERN_STR: &str = r"(?i)[?&]utm_[a-z]+=";
+
+// Codes are uppercase or digits, so "promo code below" is not one
+const PROMO_CODE_PATTERN_STR: &str = r#"(?i:промокод\w*|promo\s?code|coupon\s?code|купон)\s*[:\-—]?\s*[«"']?[A-Z0-9][A-Z0-9_\-]{3,}\b"#;
+
+// Names of the heuristics in counters and `ChannelAdFilter::skip`
+const UTM_LINKS: &str = "utm_links";
+const PROMO_CODES: &str = "promo_codes";
+const SPONSORED_FORWARDS: &str = "sponsored_forwards";
+
+#[derive(Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct AdFilterConfig {
+    pub enabled: bool,
+    /// Matched against post text and link URLs, the ad hashtag and ERID
+    /// token by default
+    pub patterns: Vec<AdPattern>,
+    /// Links with `utm_` tracking parameters
+    pub utm_links: bool,
+    /// Promo codes like `промокод SALE20`
+    pub promo_codes: bool,
+    /// Reposts from other channels, usually paid cross-promotion
+    pub sponsored_forwards: bool,
+    /// Overrides by channel handle
+    pub channels: HashMap<String, ChannelAdFilter>,
+}
+
+impl Default for AdFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: vec![AdPattern {
+                name: "ad_marker".to_string(),
+                regex: AD_PATTERN_STR.to_string(),
+            }],
+            utm_links: false,
+            promo_codes: false,
+            sponsored_forwards: false,
+            channels: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, serde::Deserialize)]
+pub struct AdPattern {
+    /// Reported in drop counters
+    pub name: String,
+    pub regex: String,
+}
+
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ChannelAdFilter {
+    /// Turn filtering on or off for this channel only
+    pub enabled: Option<bool>,
+    /// Checked in addition to the global patterns
+    pub patterns: Vec<AdPattern>,
+    /// Names of rules not applied to this channel
+    pub skip: Vec<String>,
+}
+
+/// What the pipeline looks at in a post
+pub(crate) struct Post<'a> {
+    pub channel_id: i64,
+    pub text: &'a str,
+    /// Targets of text links, not visible in `text`
+    pub urls: Vec<&'a str>,
+    /// Channel the post was forwarded from
+    pub forwarded_from: Option<i64>,
+}
+
+enum Check {
+    /// Pattern found in the text or a link
+    Pattern(Regex),
+    /// Pattern found in the text only
+    Text(Regex),
+    ForeignForward,
+}
+
+struct AdRule {
+    name: String,
+    check: Check,
+}
+
+impl AdRule {
+    fn pattern(pattern: &AdPattern) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: pattern.name.clone(),
+            check: Check::Pattern(Regex::new(&pattern.regex)?),
+        })
+    }
+
+    fn matches(&self, post: &Post) -> bool {
+        match &self.check {
+            Check::Pattern(regex) => {
+                regex.is_match(post.text) || post.urls.iter().any(|url| regex.is_match(url))
+            }
+            Check::Text(regex) => regex.is_match(post.text),
+            Check::ForeignForward => post
+                .forwarded_from
+                .is_some_and(|channel_id| channel_id != post.channel_id),
+        }
+    }
+}
+
+struct ChannelRules {
+    enabled: Option<bool>,
+    rules: Vec<AdRule>,
+    skip: HashSet<String>,
+}
+
+/// Ordered ad rules with per-channel overrides
+pub(crate) struct AdFilter {
+    enabled: bool,
+    rules: Vec<AdRule>,
+    /// Keyed by lowercase handle
+    channels: HashMap<String, ChannelRules>,
+}
+
+impl AdFilter {
+    pub(crate) fn new(config: &AdFilterConfig) -> Result<Self, regex::Error> {
+        let mut rules = config
+            .patterns
+            .iter()
+            .map(AdRule::pattern)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if config.utm_links {
+            rules.push(AdRule {
+                name: UTM_LINKS.to_string(),
+                check: Check::Pattern(Regex::new(UTM_PATTERN_STR)?),
+            });
+        }
+
+        if config.promo_codes {
+            rules.push(AdRule {
+                name: PROMO_CODES.to_string(),
+                check: Check::Text(Regex::new(PROMO_CODE_PATTERN_STR)?),
+            });
+        }
+
+        if config.sponsored_forwards {
+            rules.push(AdRule {
+                name: SPONSORED_FORWARDS.to_string(),
+                check: Check::ForeignForward,
+            });
+        }
+
+        let mut channels = HashMap::new();
+        for (handle, channel) in &config.channels {
+            let rules = channel
+                .patterns
+                .iter()
+                .map(AdRule::pattern)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            channels.insert(
+                handle.trim_start_matches('@').to_lowercase(),
+                ChannelRules {
+                    enabled: channel.enabled,
+                    rules,
+                    skip: channel.skip.iter().cloned().collect(),
+                },
+            );
+        }
+
+        Ok(Self {
+            enabled: config.enabled,
+            rules,
+            channels,
+        })
+    }
+
+    /// Name of the first rule considering the post an ad
+    pub(crate) fn matching_rule(&self, channel_handle: &str, post: &Post) -> Option<&str> {
+        let channel = self.channels.get(&channel_handle.to_lowercase());
+
+        if !channel.and_then(|c| c.enabled).unwrap_or(self.enabled) {
+            return None;
+        }
+
+        let skipped = |rule: &&AdRule| channel.is_some_and(|c| c.skip.contains(&rule.name));
+
+        self.rules
+            .iter()
+            .filter(|rule| !skipped(rule))
+            .chain(channel.iter().flat_map(|c| &c.rules))
+            .find(|rule| rule.matches(post))
+            .map(|rule| rule.name.as_str())
+    }
+}
+
+/// Dropped post counters by channel and rule, since startup
+#[derive(Default)]
+pub(crate) struct AdStats(Mutex<BTreeMap<(String, String), u64>>);
+
+impl AdStats {
+    pub(crate) fn record(&self, channel_handle: &str, rule: &str) {
+        let mut counters = self.0.lock().unwrap();
+        *counters
+            .entry((channel_handle.to_string(), rule.to_string()))
+            .or_default() += 1;
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<DroppedAds> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((channel, rule), count)| DroppedAds {
+                channel: channel.clone(),
+                rule: rule.clone(),
+                count: *count,
+            })
+            .collect()
+    }
+}
@@ -1,11 +1,26 @@
-use tgfeed_ai::{MessageData, Summarizer};
-use tgfeed_repo::models::Subscription;
+use tgfeed_ai::{MessageData, Progress, Summarizer};
+use tgfeed_common::command::SummaryEntry;
+use tgfeed_common::html::sanitize_html;
+use tgfeed_common::search::{FoundPost, SearchQuery, SearchResults};
+use tgfeed_repo::models::{MessageSearch, StoredMessage, StoredSummary, Subscription};
 
 use crate::{MonitorError, MonitorResult, MonitorService};
 
 // TODO: from config?
 const MAX_SUBSCRIPTIONS_PER_USER: usize = 30;
 
+/// How far back /ask looks for relevant posts
+const ASK_WINDOW_DAYS: i64 = 7;
+
+/// Most relevant posts given to the model to answer from
+const ASK_MAX_POSTS: i64 = 50;
+
+pub(crate) enum NewSummary {
+    NoSubscriptions,
+    NoNewMessages,
+    Generated(String),
+}
+
 impl<S: Summarizer> MonitorService<S> {
     pub(crate) async fn subscribe_to_channel(
         &self,
@@ -82,55 +97,232 @@ impl<S: Summarizer> MonitorService<S> {
             .map(|s| s.into_iter().map(|c| c.channel_handle).collect())?)
     }
 
-    pub(crate) async fn summarize(&self, user_id: i64) -> MonitorResult<String> {
+    pub(crate) async fn summarize(
+        &self,
+        user_id: i64,
+        progress: Option<Progress>,
+    ) -> MonitorResult<String> {
+        Ok(
+            match generate_summary(&self.repo, &*self.summarizer, user_id, progress).await? {
+                NewSummary::NoSubscriptions => "No subscriptions to summarize.".to_string(),
+                NewSummary::NoNewMessages => "No new messages since last summary.".to_string(),
+                NewSummary::Generated(summary) => summary,
+            },
+        )
+    }
+
+    /// Answer from posts of the user's channels matching the question
+    pub(crate) async fn ask(&self, user_id: i64, question: &str) -> MonitorResult<String> {
         let subscriptions = self.repo.get_user_subscriptions(user_id).await?;
 
         if subscriptions.is_empty() {
-            return Ok("No subscriptions to summarize.".to_string());
+            return Ok("No subscriptions to search.".to_string());
         }
 
-        let since = match self.repo.get_last_summarize_time(user_id).await {
-            Ok(time) => time,
-            Err(error) => {
-                tracing::error!(%error, "Failed to get last summarize time");
-                chrono::Utc::now() - chrono::Duration::days(3)
-            }
-        };
-
         let channels_map: std::collections::HashMap<i64, String> = subscriptions
             .into_iter()
             .map(|s| (s.channel_id, s.channel_handle))
             .collect();
 
-        let channel_ids = channels_map.keys().cloned().collect::<Vec<_>>();
+        let search = MessageSearch {
+            query: question.to_string(),
+            channel_ids: channels_map.keys().cloned().collect(),
+            since: Some(chrono::Utc::now() - chrono::Duration::days(ASK_WINDOW_DAYS)),
+            until: None,
+            offset: 0,
+            limit: ASK_MAX_POSTS,
+        };
 
-        // Get messages
-        let messages = self
-            .repo
-            .get_messages_since(&channel_ids, since, 300)
-            .await?;
+        let messages = self.repo.search_messages(&search).await?.messages;
 
         if messages.is_empty() {
-            return Ok("No new messages since last summary.".to_string());
+            return Ok(format!(
+                "Nothing related found in your channels over the last {ASK_WINDOW_DAYS} days."
+            ));
+        }
+
+        tracing::info!("answering based on {} posts", messages.len());
+
+        let answer = self
+            .summarizer
+            .answer(question, message_data(messages, &channels_map))
+            .await?;
+
+        Ok(sanitize_html(&answer.text))
+    }
+
+    pub(crate) async fn search(
+        &self,
+        user_id: i64,
+        query: SearchQuery,
+        offset: u64,
+        limit: usize,
+    ) -> MonitorResult<SearchResults> {
+        let mut subscriptions = self.repo.get_user_subscriptions(user_id).await?;
+
+        let subscribed =
+            |handle: &String, s: &Subscription| s.channel_handle.eq_ignore_ascii_case(handle);
+
+        if let Some(unknown) = query
+            .channels
+            .iter()
+            .find(|handle| !subscriptions.iter().any(|s| subscribed(handle, s)))
+        {
+            return Err(MonitorError::NotSubscribed(unknown.clone()));
+        }
+
+        if !query.channels.is_empty() {
+            subscriptions.retain(|s| query.channels.iter().any(|handle| subscribed(handle, s)));
         }
 
-        let messages_data = messages
+        let channels_map: std::collections::HashMap<i64, String> = subscriptions
             .into_iter()
-            .filter_map(|m| {
-                let channel_handle = channels_map.get(&m.channel_id)?.clone();
+            .map(|s| (s.channel_id, s.channel_handle))
+            .collect();
+
+        let search = MessageSearch {
+            query: query.text,
+            channel_ids: channels_map.keys().cloned().collect(),
+            since: query
+                .days
+                .map(|days| chrono::Utc::now() - chrono::Duration::days(days.into())),
+            until: None,
+            offset,
+            limit: limit as i64,
+        };
 
-                Some(MessageData {
-                    channel_handle,
-                    text: m.text,
-                    date: m.date,
+        let page = self.repo.search_messages(&search).await?;
+
+        Ok(SearchResults {
+            posts: page
+                .messages
+                .into_iter()
+                .filter_map(|m| {
+                    Some(FoundPost {
+                        channel_handle: channels_map.get(&m.channel_id)?.clone(),
+                        channel_id: m.channel_id,
+                        message_id: m.message_id,
+                        text: m.text,
+                        date: m.date,
+                    })
                 })
+                .collect(),
+            total: page.total,
+        })
+    }
+
+    pub(crate) async fn summary_history(
+        &self,
+        user_id: i64,
+        limit: usize,
+    ) -> MonitorResult<Vec<SummaryEntry>> {
+        let summaries = self.repo.get_user_summaries(user_id, limit as i64).await?;
+
+        Ok(summaries
+            .into_iter()
+            .map(|s| SummaryEntry {
+                since: s.since,
+                until: s.until,
+                channels: s.channels,
+                model: s.model,
+                html: s.html,
+                created_at: s.created_at,
             })
-            .collect::<Vec<_>>();
+            .collect())
+    }
+}
+
+/// Messages of known channels prepared for the model
+/// Summarize messages posted since the user's previous summary
+pub(crate) async fn generate_summary<S: Summarizer>(
+    repo: &tgfeed_repo::Repo,
+    summarizer: &S,
+    user_id: i64,
+    progress: Option<Progress>,
+) -> MonitorResult<NewSummary> {
+    let subscriptions = repo.get_user_subscriptions(user_id).await?;
+
+    if subscriptions.is_empty() {
+        return Ok(NewSummary::NoSubscriptions);
+    }
+
+    let since = match repo.get_last_summarize_time(user_id).await {
+        Ok(time) => time,
+        Err(error) => {
+            tracing::error!(%error, "Failed to get last summarize time");
+            chrono::Utc::now() - chrono::Duration::days(3)
+        }
+    };
+
+    // Messages arriving while the summary is generated belong to the next one
+    let window_end = chrono::Utc::now();
+
+    let channels_map: std::collections::HashMap<i64, String> = subscriptions
+        .into_iter()
+        .map(|s| (s.channel_id, s.channel_handle))
+        .collect();
 
-        tracing::info!("summarizing based on {} posts", messages_data.len());
+    let channel_ids = channels_map.keys().cloned().collect::<Vec<_>>();
 
-        self.repo.update_summarize_time(user_id).await?;
+    // Get messages
+    let messages = repo
+        .get_messages_since(&channel_ids, since, window_end, 300)
+        .await?;
 
-        Ok(self.summarizer.summarize(messages_data).await?)
+    if messages.is_empty() {
+        return Ok(NewSummary::NoNewMessages);
     }
+
+    let messages_data = message_data(messages, &channels_map);
+
+    tracing::info!("summarizing based on {} posts", messages_data.len());
+
+    let summary = summarizer.summarize(messages_data, progress).await?;
+
+    let html = sanitize_html(&summary.text);
+
+    let mut channels = channels_map.into_values().collect::<Vec<_>>();
+    channels.sort();
+
+    let record = StoredSummary {
+        user_id,
+        since,
+        until: window_end,
+        channels,
+        model: summary.model,
+        input_tokens: summary.usage.input_tokens as i64,
+        output_tokens: summary.usage.output_tokens as i64,
+        html: html.clone(),
+        created_at: chrono::Utc::now(),
+    };
+
+    // Losing the history entry shouldn't cost the user their summary
+    if let Err(error) = repo.store_summary(record).await {
+        tracing::error!(%error, "failed to store summary");
+    }
+
+    // Only a successful summary consumes the window
+    repo.update_summarize_time(user_id, window_end).await?;
+
+    Ok(NewSummary::Generated(html))
+}
+
+fn message_data(
+    messages: Vec<StoredMessage>,
+    channels_map: &std::collections::HashMap<i64, String>,
+) -> Vec<MessageData> {
+    messages
+        .into_iter()
+        .filter_map(|m| {
+            let channel_handle = channels_map.get(&m.channel_id)?.clone();
+
+            Some(MessageData {
+                channel_id: m.channel_id,
+                channel_handle,
+                message_id: m.message_id,
+                text: m.text,
+                date: m.date,
+            })
+        })
+        .collect()
 }
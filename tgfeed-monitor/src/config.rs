@@ -1,8 +1,12 @@
 use std::path::PathBuf;
 
+use crate::ads::AdFilterConfig;
+
 #[derive(serde::Deserialize)]
 pub struct Config {
     pub api_id: i32,
     pub api_hash: String,
     pub session_file: PathBuf,
+    #[serde(default)]
+    pub ad_filter: AdFilterConfig,
 }
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tgfeed_ai::Summarizer;
+use tgfeed_common::event::BotEvent;
+use tgfeed_common::schedule::Schedule;
+use tgfeed_repo::models::DigestSchedule;
+use tokio::task::JoinError;
+
+use crate::command::{NewSummary, generate_summary};
+use crate::{MonitorResult, MonitorService};
+
+/// How often due digests are looked up
+pub(crate) const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Digests generated at the same time, each holding an AI request
+pub(crate) const MAX_CONCURRENT_DIGESTS: usize = 2;
+
+/// Outcome of a digest task: the user and their summary, if there was news
+pub(crate) type DigestResult = (i64, MonitorResult<Option<String>>);
+
+fn to_stored(
+    user_id: i64,
+    schedule: Schedule,
+    next_run_at: chrono::DateTime<chrono::Utc>,
+) -> DigestSchedule {
+    let (every_hours, minute_of_day, utc_offset_minutes) = match schedule {
+        Schedule::Daily {
+            minute_of_day,
+            utc_offset_minutes,
+        } => (None, minute_of_day as i32, utc_offset_minutes),
+        Schedule::Every { hours } => (Some(hours as i32), 0, 0),
+    };
+
+    DigestSchedule {
+        user_id,
+        every_hours,
+        minute_of_day,
+        utc_offset_minutes,
+        next_run_at,
+    }
+}
+
+fn from_stored(stored: &DigestSchedule) -> Schedule {
+    match stored.every_hours {
+        Some(hours) => Schedule::Every {
+            hours: hours.max(1) as u32,
+        },
+        None => Schedule::Daily {
+            minute_of_day: stored.minute_of_day.max(0) as u32,
+            utc_offset_minutes: stored.utc_offset_minutes,
+        },
+    }
+}
+
+impl<S: Summarizer + 'static> MonitorService<S> {
+    pub(crate) async fn set_digest(
+        &self,
+        user_id: i64,
+        schedule: Option<Schedule>,
+    ) -> MonitorResult<()> {
+        match schedule {
+            Some(schedule) => {
+                let next_run_at = schedule.next_after(chrono::Utc::now());
+
+                self.repo
+                    .set_digest_schedule(to_stored(user_id, schedule, next_run_at))
+                    .await?;
+            }
+            None => {
+                self.repo.remove_digest_schedule(user_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_digest(&self, user_id: i64) -> MonitorResult<Option<Schedule>> {
+        Ok(self
+            .repo
+            .get_digest_schedule(user_id)
+            .await?
+            .map(|s| from_stored(&s)))
+    }
+
+    /// Reschedule due digests and generate them in the background, so the
+    /// monitor keeps handling updates and commands meanwhile
+    pub(crate) async fn run_due_digests(&mut self) {
+        let now = chrono::Utc::now();
+
+        let due = match self.repo.get_due_digest_schedules(now).await {
+            Ok(due) => due,
+            Err(error) => {
+                tracing::error!(%error, "Failed to get due digests");
+                return;
+            }
+        };
+
+        for stored in due {
+            let user_id = stored.user_id;
+            let next_run_at = from_stored(&stored).next_after(now);
+
+            // Reschedule first so a failing digest waits for its next slot
+            // instead of retrying on every check
+            if let Err(error) = self
+                .repo
+                .set_digest_schedule(DigestSchedule {
+                    next_run_at,
+                    ..stored
+                })
+                .await
+            {
+                tracing::error!(%error, %user_id, "Failed to reschedule digest");
+                continue;
+            }
+
+            match self.wants_digest(user_id).await {
+                Ok(true) => self.spawn_digest(user_id),
+                Ok(false) => {}
+                Err(error) => tracing::error!(%error, %user_id, "Failed to check digest user"),
+            }
+        }
+    }
+
+    async fn wants_digest(&self, user_id: i64) -> MonitorResult<bool> {
+        if !self.repo.is_user_allowed(user_id).await? {
+            tracing::info!(%user_id, "user is not allowed, skipping digest");
+            return Ok(false);
+        }
+
+        if self.repo.get_active_users(&[user_id]).await?.is_empty() {
+            tracing::info!(%user_id, "user is inactive, skipping digest");
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn spawn_digest(&mut self, user_id: i64) {
+        let repo = self.repo.clone();
+        let summarizer = Arc::clone(&self.summarizer);
+        let slots = Arc::clone(&self.digest_slots);
+
+        self.digests.spawn(async move {
+            let _slot = slots
+                .acquire_owned()
+                .await
+                .expect("digest semaphore is never closed");
+
+            let summary = generate_summary(&repo, &*summarizer, user_id, None)
+                .await
+                .map(|summary| match summary {
+                    NewSummary::Generated(summary) => Some(summary),
+                    NewSummary::NoSubscriptions | NewSummary::NoNewMessages => None,
+                });
+
+            (user_id, summary)
+        });
+    }
+
+    /// Queue a digest generated in the background for the bot
+    pub(crate) async fn finish_digest(&self, result: Result<DigestResult, JoinError>) {
+        let (user_id, summary) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                tracing::error!(%error, "Digest task failed");
+                return;
+            }
+        };
+
+        let summary = match summary {
+            Ok(Some(summary)) => summary,
+            Ok(None) => {
+                tracing::info!(%user_id, "nothing new, skipping digest");
+                return;
+            }
+            Err(error) => {
+                tracing::error!(%error, %user_id, "Failed to generate digest");
+                return;
+            }
+        };
+
+        if let Err(error) = self
+            .events
+            .push(&BotEvent::Digest { user_id, summary })
+            .await
+        {
+            tracing::error!(%error, "Failed to queue event for bot");
+        }
+    }
+}
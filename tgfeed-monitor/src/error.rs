@@ -18,12 +18,21 @@ pub enum MonitorError {
     #[error("Channel not found: @{0}")]
     NotFound(String),
 
+    #[error("Not subscribed to @{0}")]
+    NotSubscribed(String),
+
+    #[error("No filter #{0}")]
+    NoSuchFilter(usize),
+
     #[error("Private channels not supported")]
     EmptyHandle,
 
     #[error("AI error: {0}")]
     AI(#[from] tgfeed_ai::TgfeedAiError),
 
+    #[error("Invalid ad pattern: {0}")]
+    AdPattern(#[from] regex::Error),
+
     #[error("Subscription limit reached (max {0} channels)")]
     SubscriptionLimit(usize),
 }
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tgfeed_ai::Summarizer;
+use tgfeed_common::filter::{Filter, FilterAction, FilterChange, FilterSet, Pattern};
+use tgfeed_repo::models::FilterRule;
+
+use crate::{MonitorError, MonitorResult, MonitorService};
+
+fn to_stored(user_id: i64, channel_id: Option<i64>, filter: Filter) -> FilterRule {
+    let (pattern, regex) = match filter.pattern {
+        Pattern::Keyword(keyword) => (keyword, false),
+        Pattern::Regex(regex) => (regex, true),
+    };
+
+    FilterRule {
+        user_id,
+        channel_id,
+        exclude: filter.action == FilterAction::Exclude,
+        pattern,
+        regex,
+        created_at: chrono::Utc::now(),
+    }
+}
+
+fn action(rule: &FilterRule) -> FilterAction {
+    if rule.exclude {
+        FilterAction::Exclude
+    } else {
+        FilterAction::Include
+    }
+}
+
+fn pattern(rule: &FilterRule) -> Pattern {
+    if rule.regex {
+        Pattern::Regex(rule.pattern.clone())
+    } else {
+        Pattern::Keyword(rule.pattern.clone())
+    }
+}
+
+/// Rules of a user and their sets compiled per channel on first use
+struct UserFilters {
+    rules: Vec<FilterRule>,
+    sets: HashMap<i64, FilterSet>,
+}
+
+impl UserFilters {
+    fn allows(&mut self, channel_id: i64, text: &str) -> bool {
+        let rules = &self.rules;
+
+        self.sets
+            .entry(channel_id)
+            .or_insert_with(|| {
+                FilterSet::new(
+                    rules
+                        .iter()
+                        .filter(|rule| rule.channel_id.is_none_or(|id| id == channel_id))
+                        .map(|rule| (action(rule), pattern(rule))),
+                )
+            })
+            .allows(text)
+    }
+}
+
+/// Filters of subscribers seen so far, dropped when a user changes them
+#[derive(Default)]
+pub(crate) struct FilterCache(Mutex<HashMap<i64, UserFilters>>);
+
+impl FilterCache {
+    fn missing(&self, user_ids: &[i64]) -> Vec<i64> {
+        let cache = self.0.lock().unwrap();
+        user_ids
+            .iter()
+            .copied()
+            .filter(|user_id| !cache.contains_key(user_id))
+            .collect()
+    }
+
+    /// Caches `rules` of `user_ids`, users without rules included
+    fn insert(&self, user_ids: Vec<i64>, rules: Vec<FilterRule>) {
+        let mut rules_by_user: HashMap<i64, Vec<FilterRule>> = HashMap::new();
+        for rule in rules {
+            rules_by_user.entry(rule.user_id).or_default().push(rule);
+        }
+
+        let mut cache = self.0.lock().unwrap();
+        for user_id in user_ids {
+            let rules = rules_by_user.remove(&user_id).unwrap_or_default();
+            cache.insert(user_id, UserFilters {
+                rules,
+                sets: HashMap::new(),
+            });
+        }
+    }
+
+    fn forget(&self, user_id: i64) {
+        self.0.lock().unwrap().remove(&user_id);
+    }
+}
+
+impl<S: Summarizer> MonitorService<S> {
+    pub(crate) async fn filters(
+        &self,
+        user_id: i64,
+        change: Option<FilterChange>,
+    ) -> MonitorResult<Vec<Filter>> {
+        let subscriptions = self.repo.get_user_subscriptions(user_id).await?;
+
+        let channels_map: HashMap<i64, String> = subscriptions
+            .iter()
+            .map(|s| (s.channel_id, s.channel_handle.clone()))
+            .collect();
+
+        let change_applied = change.is_some();
+
+        match change {
+            Some(FilterChange::Add(filter)) => {
+                let channel_id = match &filter.channel {
+                    Some(handle) => Some(
+                        subscriptions
+                            .iter()
+                            .find(|s| s.channel_handle.eq_ignore_ascii_case(handle))
+                            .ok_or_else(|| MonitorError::NotSubscribed(handle.clone()))?
+                            .channel_id,
+                    ),
+                    None => None,
+                };
+
+                self.repo
+                    .add_filter(to_stored(user_id, channel_id, filter))
+                    .await?;
+            }
+            Some(FilterChange::Remove(position)) => {
+                let rules = self.listed_filters(user_id, &channels_map).await?;
+
+                let (rule, _) = position
+                    .checked_sub(1)
+                    .and_then(|index| rules.get(index))
+                    .ok_or(MonitorError::NoSuchFilter(position))?;
+
+                self.repo.remove_filter(rule).await?;
+            }
+            Some(FilterChange::Clear) => {
+                self.repo.clear_filters(user_id).await?;
+            }
+            None => (),
+        }
+
+        if change_applied {
+            self.filter_cache.forget(user_id);
+        }
+
+        Ok(self
+            .listed_filters(user_id, &channels_map)
+            .await?
+            .into_iter()
+            .map(|(_, filter)| filter)
+            .collect())
+    }
+
+    /// Rules of the user shown by /filter; rules of channels the user left
+    /// are kept but hidden, they can't match anything
+    async fn listed_filters(
+        &self,
+        user_id: i64,
+        channels_map: &HashMap<i64, String>,
+    ) -> MonitorResult<Vec<(FilterRule, Filter)>> {
+        Ok(self
+            .repo
+            .get_user_filters(user_id)
+            .await?
+            .into_iter()
+            .filter_map(|rule| {
+                let channel = match rule.channel_id {
+                    Some(channel_id) => Some(channels_map.get(&channel_id)?.clone()),
+                    None => None,
+                };
+
+                let filter = Filter {
+                    action: action(&rule),
+                    channel,
+                    pattern: pattern(&rule),
+                };
+
+                Some((rule, filter))
+            })
+            .collect())
+    }
+
+    /// Subscribers of `channel_id` whose filters let a post with `text` through
+    pub(crate) async fn accepting_subscribers(
+        &self,
+        channel_id: i64,
+        subscribers: Vec<i64>,
+        text: &str,
+    ) -> MonitorResult<Vec<i64>> {
+        let missing = self.filter_cache.missing(&subscribers);
+        if !missing.is_empty() {
+            let rules = self.repo.get_filters_of_users(&missing).await?;
+            self.filter_cache.insert(missing, rules);
+        }
+
+        let mut cache = self.filter_cache.0.lock().unwrap();
+        Ok(subscribers
+            .into_iter()
+            .filter(|user_id| {
+                cache
+                    .get_mut(user_id)
+                    .is_none_or(|filters| filters.allows(channel_id, text))
+            })
+            .collect())
+    }
+}
@@ -1,6 +1,10 @@
+mod ads;
 mod command;
 mod config;
+mod digest;
 mod error;
+mod filter;
+mod status;
 mod update;
 mod utils;
 
@@ -10,13 +14,20 @@ mod tests;
 use std::mem::MaybeUninit;
 use std::sync::Arc;
 
+pub use ads::{AdFilterConfig, AdPattern, ChannelAdFilter};
 pub use config::Config;
 pub use error::*;
+pub use status::MonitorStatus;
 use tgfeed_ai::Summarizer;
 use tgfeed_common::command::MonitorCommand;
-use tgfeed_common::event::BotEvent;
+use tgfeed_common::health::Liveness;
+use tgfeed_repo::queue::EventQueue;
+use tokio::sync::Semaphore;
 use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinSet;
 
+use crate::ads::{AdFilter, AdStats};
+use crate::filter::FilterCache;
 use crate::utils::prompt;
 
 pub struct MonitorService<S: Summarizer> {
@@ -27,17 +38,24 @@ pub struct MonitorService<S: Summarizer> {
     updates: MaybeUninit<UnboundedReceiver<grammers_session::updates::UpdatesLike>>,
     repo: tgfeed_repo::Repo,
     command_rx: mpsc::Receiver<MonitorCommand>,
-    event_tx: mpsc::Sender<BotEvent>,
-    summarizer: S,
+    events: EventQueue,
+    summarizer: Arc<S>,
+    /// Digests being generated in the background
+    digests: JoinSet<digest::DigestResult>,
+    digest_slots: Arc<Semaphore>,
+    filter_cache: FilterCache,
+    ads: AdFilter,
+    ad_stats: AdStats,
+    running: Liveness,
 }
 
-impl<S: Summarizer> MonitorService<S> {
+impl<S: Summarizer + 'static> MonitorService<S> {
     pub async fn new(
         config: &Config,
         repo: tgfeed_repo::Repo,
         summarizer: S,
         command_rx: mpsc::Receiver<MonitorCommand>,
-        event_tx: mpsc::Sender<BotEvent>,
+        events: EventQueue,
     ) -> MonitorResult<Self> {
         let session = Arc::new(grammers_session::storages::SqliteSession::open(
             &config.session_file,
@@ -59,9 +77,15 @@ impl<S: Summarizer> MonitorService<S> {
             updates: MaybeUninit::new(updates),
             api_hash: config.api_hash.clone(),
             repo,
-            summarizer,
+            summarizer: Arc::new(summarizer),
             command_rx,
-            event_tx,
+            events,
+            digests: JoinSet::new(),
+            digest_slots: Arc::new(Semaphore::new(digest::MAX_CONCURRENT_DIGESTS)),
+            filter_cache: FilterCache::default(),
+            ads: AdFilter::new(&config.ad_filter)?,
+            ad_stats: AdStats::default(),
+            running: Liveness::default(),
         };
 
         monitor.authorize().await?;
@@ -130,7 +154,16 @@ impl<S: Summarizer> MonitorService<S> {
         Ok(())
     }
 
+    pub fn status(&self) -> MonitorStatus {
+        MonitorStatus {
+            client: self.client.clone(),
+            running: self.running.clone(),
+        }
+    }
+
     pub async fn run(mut self) -> MonitorResult<()> {
+        let _running = self.running.guard();
+
         let mut updates = self.client.stream_updates(
             unsafe { self.updates.assume_init_read() },
             grammers_client::UpdatesConfiguration {
@@ -139,9 +172,15 @@ impl<S: Summarizer> MonitorService<S> {
             },
         );
 
+        let mut digest_check = tokio::time::interval(digest::DIGEST_CHECK_INTERVAL);
+
         tracing::info!("Start listening for updates...");
         loop {
             tokio::select! {
+                _ = digest_check.tick() => self.run_due_digests().await,
+
+                Some(result) = self.digests.join_next() => self.finish_digest(result).await,
+
                 Some(cmd) = self.command_rx.recv() => {
                     if matches!(cmd, MonitorCommand::Shutdown) {
                         tracing::warn!("received shutdown command");
@@ -235,12 +274,79 @@ impl<S: Summarizer> MonitorService<S> {
                     .send(result.map_err(|e| e.to_string()))
                     .expect("broken channel");
             }
-            MonitorCommand::Summarize { user_id, response } => {
-                let result = self.summarize(user_id).await;
+            MonitorCommand::Summarize {
+                user_id,
+                progress,
+                response,
+            } => {
+                let result = self.summarize(user_id, progress).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::Ask {
+                user_id,
+                question,
+                response,
+            } => {
+                let result = self.ask(user_id, &question).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::Search {
+                user_id,
+                query,
+                offset,
+                limit,
+                response,
+            } => {
+                let result = self.search(user_id, query, offset, limit).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::SummaryHistory {
+                user_id,
+                limit,
+                response,
+            } => {
+                let result = self.summary_history(user_id, limit).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::SetDigest {
+                user_id,
+                schedule,
+                response,
+            } => {
+                let result = self.set_digest(user_id, schedule).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::GetDigest { user_id, response } => {
+                let result = self.get_digest(user_id).await;
                 response
                     .send(result.map_err(|e| e.to_string()))
                     .expect("broken channel");
             }
+            MonitorCommand::Filters {
+                user_id,
+                change,
+                response,
+            } => {
+                let result = self.filters(user_id, change).await;
+                response
+                    .send(result.map_err(|e| e.to_string()))
+                    .expect("broken channel");
+            }
+            MonitorCommand::DroppedAds { response } => {
+                response
+                    .send(Ok(self.ad_stats.snapshot()))
+                    .expect("broken channel");
+            }
             MonitorCommand::Shutdown => (),
         }
     }
@@ -0,0 +1,20 @@
+use tgfeed_common::health::Liveness;
+
+use crate::MonitorResult;
+
+/// Handle for probing the monitor state from outside of its run loop
+#[derive(Clone)]
+pub struct MonitorStatus {
+    pub(crate) client: grammers_client::Client,
+    pub(crate) running: Liveness,
+}
+
+impl MonitorStatus {
+    pub async fn is_authorized(&self) -> MonitorResult<bool> {
+        Ok(self.client.is_authorized().await?)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_alive()
+    }
+}
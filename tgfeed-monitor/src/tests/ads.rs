@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use tgfeed_common::command::DroppedAds;
+
+use crate::ads::{AdFilter, AdFilterConfig, AdPattern, AdStats, ChannelAdFilter, Post};
+
+const CHANNEL_ID: i64 = 100;
+
+fn post(text: &str) -> Post<'_> {
+    Post {
+        channel_id: CHANNEL_ID,
+        text,
+        urls: Vec::new(),
+        forwarded_from: None,
+    }
+}
+
+fn all_heuristics() -> AdFilterConfig {
+    AdFilterConfig {
+        utm_links: true,
+        promo_codes: true,
+        sponsored_forwards: true,
+        ..Default::default()
+    }
+}
+
+fn pattern(name: &str, regex: &str) -> AdPattern {
+    AdPattern {
+        name: name.to_string(),
+        regex: regex.to_string(),
+    }
+}
+
+#[test]
+fn test_default_config_drops_marked_ads_only() {
+    let ads = AdFilter::new(&AdFilterConfig::default()).unwrap();
+
+    assert_eq!(
+        ads.matching_rule("news", &post("Скидки! #реклама")),
+        Some("ad_marker")
+    );
+    assert_eq!(ads.matching_rule("news", &post("promo code SALE20")), None);
+    assert_eq!(
+        ads.matching_rule("news", &post("https://shop.example/?utm_source=tg")),
+        None
+    );
+}
+
+#[test]
+fn test_patterns_match_link_targets() {
+    let ads = AdFilter::new(&AdFilterConfig::default()).unwrap();
+
+    let post = Post {
+        urls: vec!["https://example.com/?erid=LjN8KXck9"],
+        ..post("Click here")
+    };
+
+    assert_eq!(ads.matching_rule("news", &post), Some("ad_marker"));
+}
+
+#[test]
+fn test_heuristics() {
+    let ads = AdFilter::new(&all_heuristics()).unwrap();
+
+    assert_eq!(
+        ads.matching_rule("news", &post("https://shop.example/?a=1&utm_source=tg")),
+        Some("utm_links")
+    );
+    assert_eq!(
+        ads.matching_rule("news", &post("Скидка 20% по промокоду SALE20")),
+        Some("promo_codes")
+    );
+    assert_eq!(
+        ads.matching_rule("news", &post("Use the promo code below to save")),
+        None
+    );
+
+    let repost = |from| Post {
+        forwarded_from: Some(from),
+        ..post("Subscribe to our friends")
+    };
+    assert_eq!(
+        ads.matching_rule("news", &repost(200)),
+        Some("sponsored_forwards")
+    );
+    assert_eq!(ads.matching_rule("news", &repost(CHANNEL_ID)), None);
+}
+
+#[test]
+fn test_configured_patterns_replace_builtin() {
+    let ads = AdFilter::new(&AdFilterConfig {
+        patterns: vec![pattern("casino", "(?i)casino")],
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(
+        ads.matching_rule("news", &post("Best CASINO")),
+        Some("casino")
+    );
+    assert_eq!(ads.matching_rule("news", &post("#реклама")), None);
+}
+
+#[test]
+fn test_invalid_pattern_is_rejected() {
+    let config = AdFilterConfig {
+        patterns: vec![pattern("broken", "(unclosed")],
+        ..Default::default()
+    };
+
+    assert!(AdFilter::new(&config).is_err());
+}
+
+#[test]
+fn test_channel_overrides() {
+    let channels = HashMap::from([
+        ("@Deals".to_string(), ChannelAdFilter {
+            skip: vec!["promo_codes".to_string()],
+            patterns: vec![pattern("giveaway", "(?i)giveaway")],
+            ..Default::default()
+        }),
+        ("trusted".to_string(), ChannelAdFilter {
+            enabled: Some(false),
+            ..Default::default()
+        }),
+    ]);
+
+    let ads = AdFilter::new(&AdFilterConfig {
+        channels,
+        ..all_heuristics()
+    })
+    .unwrap();
+
+    let promo = post("Промокод: SALE20");
+    assert_eq!(ads.matching_rule("deals", &promo), None);
+    assert_eq!(ads.matching_rule("news", &promo), Some("promo_codes"));
+
+    let giveaway = post("Giveaway tomorrow");
+    assert_eq!(ads.matching_rule("deals", &giveaway), Some("giveaway"));
+    assert_eq!(ads.matching_rule("news", &giveaway), None);
+
+    assert_eq!(ads.matching_rule("Trusted", &post("#реклама")), None);
+}
+
+#[test]
+fn test_disabled_filter_only_applies_enabled_channels() {
+    let ads = AdFilter::new(&AdFilterConfig {
+        enabled: false,
+        channels: HashMap::from([("noisy".to_string(), ChannelAdFilter {
+            enabled: Some(true),
+            ..Default::default()
+        })]),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(ads.matching_rule("news", &post("#реклама")), None);
+    assert_eq!(
+        ads.matching_rule("noisy", &post("#реклама")),
+        Some("ad_marker")
+    );
+}
+
+#[test]
+fn test_stats_count_by_channel_and_rule() {
+    let stats = AdStats::default();
+
+    stats.record("news", "ad_marker");
+    stats.record("deals", "promo_codes");
+    stats.record("news", "ad_marker");
+
+    let dropped = |channel: &str, rule: &str, count| DroppedAds {
+        channel: channel.to_string(),
+        rule: rule.to_string(),
+        count,
+    };
+
+    assert_eq!(stats.snapshot(), vec![
+        dropped("deals", "promo_codes", 1),
+        dropped("news", "ad_marker", 2),
+    ]);
+}
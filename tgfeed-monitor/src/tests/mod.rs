@@ -1 +1,2 @@
+mod ads;
 mod regex;
@@ -1,4 +1,10 @@
-use crate::update::get_ad_pattern;
+use regex::Regex;
+
+use crate::ads::AD_PATTERN_STR;
+
+fn get_ad_pattern() -> Regex {
+    Regex::new(AD_PATTERN_STR).unwrap()
+}
 
 #[test]
 fn test_ad_pattern_hashtag_lowercase() {
@@ -1,22 +1,11 @@
-use std::sync::OnceLock;
-
-use grammers_client::grammers_tl_types::enums::MessageEntity;
-use regex::Regex;
+use grammers_client::grammers_tl_types::enums::{MessageEntity, MessageFwdHeader, Peer};
 use tgfeed_ai::Summarizer;
 use tgfeed_common::event::BotEvent;
 use tgfeed_repo::models::StoredMessage;
 
+use crate::ads::Post;
 use crate::{MonitorError, MonitorResult, MonitorService};
 
-// ERID tokens are typically 8+ characters, alphanumeric
-pub(crate) const AD_PATTERN_STR: &str = r"(?i:#реклама|(?:^|[\s\/\\?&])erid[\s:=]+[a-z0-9]{8,})";
-
-static AD_PATTERN: OnceLock<Regex> = OnceLock::new();
-
-pub(crate) fn get_ad_pattern() -> &'static Regex {
-    AD_PATTERN.get_or_init(|| Regex::new(AD_PATTERN_STR).unwrap())
-}
-
 impl<S: Summarizer> MonitorService<S> {
     pub(crate) async fn handle_update(&self, update: grammers_client::Update) -> MonitorResult<()> {
         match update {
@@ -63,37 +52,44 @@ impl<S: Summarizer> MonitorService<S> {
                     return Ok(());
                 }
 
-                // Skip ads: messages with an ad hashtag or an Erid token
-                if get_ad_pattern().is_match(&text) {
-                    tracing::info!(
-                        %channel_handle,
-                        %message_id,
-                        "skipping ad message"
-                    );
-
-                    return Ok(());
-                }
-
-                let erid_in_url = message
+                let urls = message
                     .fmt_entities()
                     .map(|entities| {
-                        entities.iter().any(|entity| {
-                            if let MessageEntity::TextUrl(entity_url) = entity {
-                                get_ad_pattern().is_match(&entity_url.url)
-                            } else {
-                                false
-                            }
-                        })
+                        entities
+                            .iter()
+                            .filter_map(|entity| match entity {
+                                MessageEntity::TextUrl(entity_url) => Some(entity_url.url.as_str()),
+                                _ => None,
+                            })
+                            .collect()
                     })
-                    .unwrap_or(false);
+                    .unwrap_or_default();
+
+                let forwarded_from = message.forward_header().and_then(|header| {
+                    let MessageFwdHeader::Header(header) = header;
+                    match &header.from_id {
+                        Some(Peer::Channel(channel)) => Some(channel.channel_id),
+                        _ => None,
+                    }
+                });
+
+                let post = Post {
+                    channel_id,
+                    text: &text,
+                    urls,
+                    forwarded_from,
+                };
 
-                if erid_in_url {
+                if let Some(rule) = self.ads.matching_rule(&channel_handle, &post) {
                     tracing::info!(
                         %channel_handle,
                         %message_id,
+                        %rule,
                         "skipping ad message"
                     );
 
+                    self.ad_stats.record(&channel_handle, rule);
+
                     return Ok(());
                 }
 
@@ -114,6 +110,31 @@ impl<S: Summarizer> MonitorService<S> {
 
                 match self.repo.get_channel_subscribers(channel_id).await {
                     Ok(subscribers) => {
+                        // Users the bot can't reach get nothing until they /start again
+                        let subscribers = match self.repo.get_active_users(&subscribers).await {
+                            Ok(active) => active,
+                            Err(error) => {
+                                tracing::error!(%error, "Failed to get active users");
+                                subscribers
+                            }
+                        };
+
+                        // Filters are best effort, a failed lookup forwards to everyone
+                        let subscribers = match self
+                            .accepting_subscribers(channel_id, subscribers.clone(), &text)
+                            .await
+                        {
+                            Ok(accepting) => accepting,
+                            Err(error) => {
+                                tracing::error!(%error, "Failed to apply filters");
+                                subscribers
+                            }
+                        };
+
+                        if subscribers.is_empty() {
+                            return Ok(());
+                        }
+
                         let event = BotEvent::NewMessage {
                             channel_id,
                             channel_handle,
@@ -123,8 +144,8 @@ impl<S: Summarizer> MonitorService<S> {
                             entities,
                         };
 
-                        if let Err(error) = self.event_tx.send(event).await {
-                            tracing::error!(%error, "Failed sending event to bot");
+                        if let Err(error) = self.events.push(&event).await {
+                            tracing::error!(%error, "Failed to queue event for bot");
                         }
                     }
                     Err(error) => {
@@ -132,6 +153,76 @@ impl<S: Summarizer> MonitorService<S> {
                     }
                 };
             }
+            grammers_client::Update::MessageEdited(message) if !message.outgoing() => {
+                let peer_id = message.peer_ref().id;
+
+                if peer_id.kind() != grammers_session::types::PeerKind::Channel {
+                    return Ok(());
+                }
+
+                let channel_id = peer_id.bare_id();
+
+                if !self.repo.is_subscribed(channel_id).await? {
+                    return Ok(());
+                }
+
+                let channel_handle = message
+                    .peer()
+                    .ok()
+                    .and_then(|p| Self::get_handle(p))
+                    .ok_or_else(|| MonitorError::EmptyHandle)?;
+
+                let message_id = message.id();
+                let text = message.text().to_string();
+
+                // Delivered copies can't lose their text
+                if text.is_empty() {
+                    return Ok(());
+                }
+
+                tracing::info!(%channel_handle, %message_id, "message edited");
+
+                self.repo
+                    .update_message_text(channel_id, message_id, &text)
+                    .await?;
+
+                let event = BotEvent::PostEdited {
+                    channel_id,
+                    channel_handle,
+                    message_id,
+                    text,
+                    entities: tgfeed_common::utils::convert_entities(message.fmt_entities()),
+                };
+
+                if let Err(error) = self.events.push(&event).await {
+                    tracing::error!(%error, "Failed to queue event for bot");
+                }
+            }
+            grammers_client::Update::MessageDeleted(deletion) => {
+                // Deletions without a channel are from private chats and groups
+                let Some(channel_id) = deletion.channel_id() else {
+                    return Ok(());
+                };
+
+                if !self.repo.is_subscribed(channel_id).await? {
+                    return Ok(());
+                }
+
+                let message_ids = deletion.messages().to_vec();
+
+                tracing::info!(%channel_id, ?message_ids, "messages deleted");
+
+                self.repo.delete_messages(channel_id, &message_ids).await?;
+
+                let event = BotEvent::PostsDeleted {
+                    channel_id,
+                    message_ids,
+                };
+
+                if let Err(error) = self.events.push(&event).await {
+                    tracing::error!(%error, "Failed to queue event for bot");
+                }
+            }
             _ => {}
         }
 
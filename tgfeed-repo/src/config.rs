@@ -1,5 +1,78 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
 #[derive(serde::Deserialize)]
-pub struct Config {
+#[serde(rename_all = "snake_case")]
+pub enum Config {
+    Mongodb(MongoConfig),
+    Sqlite(SqliteConfig),
+}
+
+#[derive(serde::Deserialize)]
+pub struct MongoConfig {
     pub connection_string: String,
     pub database_name: String,
+    /// Apply pending migrations on startup instead of refusing to start
+    #[serde(default = "default_auto_migrate")]
+    pub auto_migrate: bool,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SqliteConfig {
+    /// Database file, created if missing
+    pub path: PathBuf,
+}
+
+#[derive(serde::Deserialize)]
+pub struct RetentionConfig {
+    /// Messages older than this are deleted
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u32,
+    /// Keep at most this many newest messages per channel
+    #[serde(default)]
+    pub max_messages_per_channel: Option<u64>,
+    /// How often pruning runs, at least once a second
+    #[serde(
+        default = "default_prune_interval_secs",
+        deserialize_with = "positive_interval"
+    )]
+    pub prune_interval_secs: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: default_max_age_days(),
+            max_messages_per_channel: None,
+            prune_interval_secs: default_prune_interval_secs(),
+        }
+    }
+}
+
+fn default_auto_migrate() -> bool {
+    true
+}
+
+fn default_max_age_days() -> u32 {
+    30
+}
+
+fn default_prune_interval_secs() -> u64 {
+    3600
+}
+
+pub(crate) fn positive_interval<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let secs = u64::deserialize(deserializer)?;
+
+    if secs == 0 {
+        return Err(serde::de::Error::custom(
+            "interval must be at least 1 second",
+        ));
+    }
+
+    Ok(secs)
 }
@@ -5,6 +5,15 @@ pub enum TgFeedRepoError {
 
     #[error("serialization error: {0}")]
     MongodbSerializationError(#[from] mongodb::bson::ser::Error),
+
+    #[error("sqlite error: {0}")]
+    SqliteError(#[from] sqlite::Error),
+
+    #[error("database schema is at version {current}, expected {target}: run `tgfeed migrate`")]
+    PendingMigrations { current: u32, target: u32 },
+
+    #[error("storage task failed: {0}")]
+    TaskError(#[from] tokio::task::JoinError),
 }
 
 pub type TgFeedRepoResult<T> = Result<T, TgFeedRepoError>;
@@ -1,117 +1,161 @@
 mod config;
 mod error;
-mod message;
+pub mod memory;
 pub mod models;
-mod subscription;
-mod summarize;
-mod user;
+pub mod mongo;
+pub mod queue;
+mod retention;
+mod search;
+pub mod sqlite;
+pub mod storage;
 
-pub use config::Config;
+#[cfg(test)]
+mod tests;
+
+pub use config::{Config, MongoConfig, RetentionConfig, SqliteConfig};
 pub use error::{TgFeedRepoError, TgFeedRepoResult};
 
-use crate::models::{StoredMessage, Subscription, SummarizeState, User};
+use crate::memory::MemoryStorage;
+use crate::models::{
+    Delivery, DigestSchedule, FilterRule, MessageSearch, QueuedEvent, SearchPage, StoredMessage,
+    StoredSummary, Subscription, User,
+};
+use crate::mongo::MongoStorage;
+use crate::sqlite::SqliteStorage;
+use crate::storage::{
+    DeliveryStore, DigestStore, EventQueueStore, FilterStore, MessageStore, Storage,
+    SubscriptionStore, SummarizeStore, SummaryStore, UserStore,
+};
 
 #[derive(Clone)]
 pub struct Repo {
-    db: mongodb::Database,
+    backend: Backend,
 }
 
-impl Repo {
-    pub async fn new(config: &Config) -> TgFeedRepoResult<Self> {
-        let client_options =
-            mongodb::options::ClientOptions::parse(&config.connection_string).await?;
-
-        let client = mongodb::Client::with_options(client_options)?;
-
-        let db = client.database(&config.database_name);
+#[derive(Clone)]
+enum Backend {
+    Mongo(MongoStorage),
+    Sqlite(SqliteStorage),
+    Memory(MemoryStorage),
+}
 
-        let this = Self { db };
-        this.create_indexes().await?;
+/// Forward [`Repo`] methods to the configured backend
+macro_rules! delegate {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        impl Repo {
+            $(
+                pub async fn $name(&self $(, $arg: $ty)*) -> TgFeedRepoResult<$ret> {
+                    match &self.backend {
+                        Backend::Mongo(storage) => storage.$name($($arg),*).await,
+                        Backend::Sqlite(storage) => storage.$name($($arg),*).await,
+                        Backend::Memory(storage) => storage.$name($($arg),*).await,
+                    }
+                }
+            )*
+        }
+    };
+}
 
-        Ok(this)
-    }
+impl Repo {
+    pub async fn new(config: &Config) -> TgFeedRepoResult<Self> {
+        let backend = match config {
+            Config::Mongodb(config) => Backend::Mongo(MongoStorage::new(config).await?),
+            Config::Sqlite(config) => Backend::Sqlite(SqliteStorage::open(config).await?),
+        };
 
-    async fn create_indexes(&self) -> TgFeedRepoResult<()> {
-        use mongodb::IndexModel;
-        use mongodb::bson::doc;
-        use mongodb::options::IndexOptions;
-
-        // Subscriptions indexes
-        self.subscriptions()
-            .create_index(IndexModel::builder().keys(doc! { "user_id": 1 }).build())
-            .await?;
-
-        self.subscriptions()
-            .create_index(IndexModel::builder().keys(doc! { "channel_id": 1 }).build())
-            .await?;
-
-        // Unique constraint: one subscription per user per channel
-        self.subscriptions()
-            .create_index(
-                IndexModel::builder()
-                    .keys(doc! { "user_id": 1, "channel_id": 1 })
-                    .options(IndexOptions::builder().unique(true).build())
-                    .build(),
-            )
-            .await?;
-
-        // Messages indexes
-        self.messages()
-            .create_index(
-                IndexModel::builder()
-                    .keys(doc! { "channel_id": 1, "date": -1 })
-                    .build(),
-            )
-            .await?;
-
-        // Unique constraint: one message per channel per message_id
-        self.messages()
-            .create_index(
-                IndexModel::builder()
-                    .keys(doc! { "channel_id": 1, "message_id": 1 })
-                    .options(IndexOptions::builder().unique(true).build())
-                    .build(),
-            )
-            .await?;
-
-        // Summarize state index
-        self.summarize_state()
-            .create_index(
-                IndexModel::builder()
-                    .keys(doc! { "user_id": 1 })
-                    .options(IndexOptions::builder().unique(true).build())
-                    .build(),
-            )
-            .await?;
-
-        // Users index
-        self.users()
-            .create_index(
-                IndexModel::builder()
-                    .keys(doc! { "telegram_id": 1 })
-                    .options(IndexOptions::builder().unique(true).build())
-                    .build(),
-            )
-            .await?;
-
-        tracing::info!("Database indexes created/verified");
-
-        Ok(())
+        Ok(Self { backend })
     }
 
-    fn subscriptions(&self) -> mongodb::Collection<Subscription> {
-        self.db.collection("subscriptions")
+    /// Repo kept entirely in process memory, for tests
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(MemoryStorage::new()),
+        }
     }
 
-    fn messages(&self) -> mongodb::Collection<StoredMessage> {
-        self.db.collection("messages")
+    pub async fn is_subscribed(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
+        self.has_subscribers(channel_id).await
     }
 
-    fn summarize_state(&self) -> mongodb::Collection<SummarizeState> {
-        self.db.collection("summarize_state")
+    pub async fn get_last_summarize_time(
+        &self,
+        user_id: i64,
+    ) -> TgFeedRepoResult<chrono::DateTime<chrono::Utc>> {
+        let last = match &self.backend {
+            Backend::Mongo(storage) => storage.get_last_summarize_time(user_id).await?,
+            Backend::Sqlite(storage) => storage.get_last_summarize_time(user_id).await?,
+            Backend::Memory(storage) => storage.get_last_summarize_time(user_id).await?,
+        };
+
+        // Default to 3 days ago if never summarized
+        Ok(last.unwrap_or_else(|| chrono::Utc::now() - chrono::Duration::days(3)))
     }
+}
 
-    fn users(&self) -> mongodb::Collection<User> {
-        self.db.collection("users")
-    }
+delegate! {
+    fn ping(&self) -> ();
+
+    fn store_message(&self, msg: StoredMessage) -> ();
+    fn get_messages_since(
+        &self,
+        channel_ids: &[i64],
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+        limit: i64
+    ) -> Vec<StoredMessage>;
+    fn search_messages(&self, search: &MessageSearch) -> SearchPage;
+    fn update_message_text(&self, channel_id: i64, message_id: i32, text: &str) -> bool;
+    fn delete_messages(&self, channel_id: i64, message_ids: &[i32]) -> u64;
+    fn delete_messages_before(&self, before: chrono::DateTime<chrono::Utc>) -> u64;
+    fn trim_channel_messages(&self, max_per_channel: u64) -> u64;
+
+    fn add_subscription(&self, sub: Subscription) -> ();
+    fn remove_subscription_by_handle(&self, user_id: i64, channel_handle: &str) -> bool;
+    fn remove_subscription(&self, user_id: i64, channel_id: i64) -> bool;
+    fn update_subscription_handle(&self, channel_id: i64, new_handle: &str) -> ();
+    fn get_user_subscriptions(&self, user_id: i64) -> Vec<Subscription>;
+    fn get_channel_subscribers(&self, channel_id: i64) -> Vec<i64>;
+    fn is_user_subscribed(&self, user_id: i64, channel_id: i64) -> bool;
+    fn has_subscribers(&self, channel_id: i64) -> bool;
+    fn get_subscribed_channels(&self) -> Vec<i64>;
+
+    fn update_summarize_time(&self, user_id: i64, at: chrono::DateTime<chrono::Utc>) -> ();
+
+    fn store_summary(&self, summary: StoredSummary) -> ();
+    fn get_user_summaries(&self, user_id: i64, limit: i64) -> Vec<StoredSummary>;
+
+    fn set_digest_schedule(&self, schedule: DigestSchedule) -> ();
+    fn remove_digest_schedule(&self, user_id: i64) -> bool;
+    fn get_digest_schedule(&self, user_id: i64) -> Option<DigestSchedule>;
+    fn get_due_digest_schedules(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<DigestSchedule>;
+
+    fn add_filter(&self, rule: FilterRule) -> ();
+    fn remove_filter(&self, rule: &FilterRule) -> bool;
+    fn clear_filters(&self, user_id: i64) -> u64;
+    fn get_user_filters(&self, user_id: i64) -> Vec<FilterRule>;
+    fn get_filters_of_users(&self, user_ids: &[i64]) -> Vec<FilterRule>;
+
+    fn record_delivery(&self, delivery: Delivery) -> ();
+    fn get_delivery(&self, channel_id: i64, message_id: i32, user_id: i64) -> Option<Delivery>;
+    fn get_post_deliveries(&self, channel_id: i64, message_id: i32) -> Vec<Delivery>;
+    fn get_user_deliveries(&self, user_id: i64, limit: i64) -> Vec<Delivery>;
+    fn delete_deliveries_before(&self, before: chrono::DateTime<chrono::Utc>) -> u64;
+
+    fn enqueue_event(&self, payload: String) -> ();
+    fn get_due_events(&self, now: chrono::DateTime<chrono::Utc>, limit: i64) -> Vec<QueuedEvent>;
+    fn ack_event(&self, id: &str) -> bool;
+    fn retry_event(
+        &self,
+        id: &str,
+        error: &str,
+        available_at: chrono::DateTime<chrono::Utc>
+    ) -> ();
+    fn dead_letter_event(&self, id: &str, error: &str) -> ();
+    fn count_events(&self, dead: bool) -> u64;
+
+    fn is_user_allowed(&self, user_id: i64) -> bool;
+    fn set_user_allowed(&self, user_id: i64, allowed: bool) -> ();
+    fn set_user_active(&self, user_id: i64, active: bool) -> bool;
+    fn get_active_users(&self, user_ids: &[i64]) -> Vec<i64>;
+    fn get_users(&self) -> Vec<User>;
 }
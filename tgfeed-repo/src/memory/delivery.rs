@@ -0,0 +1,72 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::Delivery;
+use crate::storage::DeliveryStore;
+
+impl DeliveryStore for MemoryStorage {
+    async fn record_delivery(&self, delivery: Delivery) -> TgFeedRepoResult<()> {
+        self.state().deliveries.insert(
+            (delivery.channel_id, delivery.message_id, delivery.user_id),
+            delivery,
+        );
+
+        Ok(())
+    }
+
+    async fn get_delivery(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        user_id: i64,
+    ) -> TgFeedRepoResult<Option<Delivery>> {
+        Ok(self
+            .state()
+            .deliveries
+            .get(&(channel_id, message_id, user_id))
+            .cloned())
+    }
+
+    async fn get_post_deliveries(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        Ok(self
+            .state()
+            .deliveries
+            .range((channel_id, message_id, i64::MIN)..=(channel_id, message_id, i64::MAX))
+            .map(|(_, delivery)| delivery.clone())
+            .collect())
+    }
+
+    async fn get_user_deliveries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        let mut deliveries = self
+            .state()
+            .deliveries
+            .values()
+            .filter(|d| d.user_id == user_id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        deliveries.sort_by_key(|d| std::cmp::Reverse(d.attempted_at));
+        deliveries.truncate(limit.max(0) as usize);
+
+        Ok(deliveries)
+    }
+
+    async fn delete_deliveries_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<u64> {
+        let mut state = self.state();
+
+        let count = state.deliveries.len();
+        state.deliveries.retain(|_, d| d.attempted_at >= before);
+
+        Ok((count - state.deliveries.len()) as u64)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::DigestSchedule;
+use crate::storage::DigestStore;
+
+impl DigestStore for MemoryStorage {
+    async fn set_digest_schedule(&self, schedule: DigestSchedule) -> TgFeedRepoResult<()> {
+        self.state()
+            .digest_schedules
+            .insert(schedule.user_id, schedule);
+
+        Ok(())
+    }
+
+    async fn remove_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        Ok(self.state().digest_schedules.remove(&user_id).is_some())
+    }
+
+    async fn get_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<Option<DigestSchedule>> {
+        Ok(self.state().digest_schedules.get(&user_id).cloned())
+    }
+
+    async fn get_due_digest_schedules(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<Vec<DigestSchedule>> {
+        Ok(self
+            .state()
+            .digest_schedules
+            .values()
+            .filter(|s| s.next_run_at <= now)
+            .cloned()
+            .collect())
+    }
+}
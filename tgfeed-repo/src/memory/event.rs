@@ -0,0 +1,96 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::QueuedEvent;
+use crate::storage::EventQueueStore;
+
+impl MemoryStorage {
+    /// Apply `f` to the queued event `id`, if any
+    fn update_event(&self, id: &str, f: impl FnOnce(&mut QueuedEvent)) {
+        let Ok(id) = id.parse::<u64>() else {
+            return;
+        };
+
+        if let Some(event) = self.state().events.get_mut(&id) {
+            f(event);
+        }
+    }
+}
+
+impl EventQueueStore for MemoryStorage {
+    async fn enqueue_event(&self, payload: String) -> TgFeedRepoResult<()> {
+        let mut state = self.state();
+
+        state.last_event_id += 1;
+        let id = state.last_event_id;
+        let now = chrono::Utc::now();
+
+        state.events.insert(id, QueuedEvent {
+            id: id.to_string(),
+            payload,
+            attempts: 0,
+            last_error: None,
+            available_at: now,
+            dead: false,
+            created_at: now,
+        });
+
+        Ok(())
+    }
+
+    async fn get_due_events(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<QueuedEvent>> {
+        Ok(self
+            .state()
+            .events
+            .values()
+            .filter(|e| !e.dead && e.available_at <= now)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn ack_event(&self, id: &str) -> TgFeedRepoResult<bool> {
+        let Ok(id) = id.parse::<u64>() else {
+            return Ok(false);
+        };
+
+        Ok(self.state().events.remove(&id).is_some())
+    }
+
+    async fn retry_event(
+        &self,
+        id: &str,
+        error: &str,
+        available_at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
+        self.update_event(id, |event| {
+            event.attempts += 1;
+            event.last_error = Some(error.to_string());
+            event.available_at = available_at;
+        });
+
+        Ok(())
+    }
+
+    async fn dead_letter_event(&self, id: &str, error: &str) -> TgFeedRepoResult<()> {
+        self.update_event(id, |event| {
+            event.attempts += 1;
+            event.last_error = Some(error.to_string());
+            event.dead = true;
+        });
+
+        Ok(())
+    }
+
+    async fn count_events(&self, dead: bool) -> TgFeedRepoResult<u64> {
+        Ok(self
+            .state()
+            .events
+            .values()
+            .filter(|e| e.dead == dead)
+            .count() as u64)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::FilterRule;
+use crate::storage::FilterStore;
+
+/// Same rule apart from when it was added
+fn same_rule(a: &FilterRule, b: &FilterRule) -> bool {
+    a.user_id == b.user_id
+        && a.channel_id == b.channel_id
+        && a.exclude == b.exclude
+        && a.pattern == b.pattern
+        && a.regex == b.regex
+}
+
+impl FilterStore for MemoryStorage {
+    async fn add_filter(&self, rule: FilterRule) -> TgFeedRepoResult<()> {
+        let mut state = self.state();
+
+        state.filters.retain(|r| !same_rule(r, &rule));
+        state.filters.push(rule);
+
+        Ok(())
+    }
+
+    async fn remove_filter(&self, rule: &FilterRule) -> TgFeedRepoResult<bool> {
+        let mut state = self.state();
+
+        let count = state.filters.len();
+        state.filters.retain(|r| !same_rule(r, rule));
+
+        Ok(state.filters.len() < count)
+    }
+
+    async fn clear_filters(&self, user_id: i64) -> TgFeedRepoResult<u64> {
+        let mut state = self.state();
+
+        let count = state.filters.len();
+        state.filters.retain(|r| r.user_id != user_id);
+
+        Ok((count - state.filters.len()) as u64)
+    }
+
+    async fn get_user_filters(&self, user_id: i64) -> TgFeedRepoResult<Vec<FilterRule>> {
+        self.get_filters_of_users(&[user_id]).await
+    }
+
+    async fn get_filters_of_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<FilterRule>> {
+        Ok(self
+            .state()
+            .filters
+            .iter()
+            .filter(|r| user_ids.contains(&r.user_id))
+            .cloned()
+            .collect())
+    }
+}
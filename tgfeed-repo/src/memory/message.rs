@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+
+use crate::memory::MemoryStorage;
+use crate::models::{MessageSearch, SearchPage, StoredMessage};
+use crate::storage::MessageStore;
+use crate::{TgFeedRepoResult, search};
+
+impl MessageStore for MemoryStorage {
+    async fn store_message(&self, msg: StoredMessage) -> TgFeedRepoResult<()> {
+        self.state()
+            .messages
+            .insert((msg.channel_id, msg.message_id), msg);
+
+        Ok(())
+    }
+
+    async fn get_messages_since(
+        &self,
+        channel_ids: &[i64],
+        since: chrono::DateTime<Utc>,
+        until: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredMessage>> {
+        let mut messages = self
+            .state()
+            .messages
+            .values()
+            .filter(|m| channel_ids.contains(&m.channel_id) && (since..until).contains(&m.date))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        messages.sort_by_key(|m| std::cmp::Reverse(m.date));
+        messages.truncate(limit.max(0) as usize);
+
+        Ok(messages)
+    }
+
+    async fn search_messages(&self, search: &MessageSearch) -> TgFeedRepoResult<SearchPage> {
+        let state = self.state();
+
+        let messages = state
+            .messages
+            .values()
+            .filter(|m| search::in_scope(m, search))
+            .cloned();
+
+        Ok(search::page(messages, search))
+    }
+
+    async fn update_message_text(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        text: &str,
+    ) -> TgFeedRepoResult<bool> {
+        match self.state().messages.get_mut(&(channel_id, message_id)) {
+            Some(message) => {
+                message.text = text.to_string();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete_messages(&self, channel_id: i64, message_ids: &[i32]) -> TgFeedRepoResult<u64> {
+        let mut state = self.state();
+
+        Ok(message_ids
+            .iter()
+            .filter(|id| state.messages.remove(&(channel_id, **id)).is_some())
+            .count() as u64)
+    }
+
+    async fn delete_messages_before(&self, before: chrono::DateTime<Utc>) -> TgFeedRepoResult<u64> {
+        let mut state = self.state();
+
+        let count = state.messages.len();
+        state.messages.retain(|_, m| m.date >= before);
+
+        Ok((count - state.messages.len()) as u64)
+    }
+
+    async fn trim_channel_messages(&self, max_per_channel: u64) -> TgFeedRepoResult<u64> {
+        let mut state = self.state();
+
+        let mut by_channel = HashMap::<i64, Vec<_>>::new();
+        for (key, message) in &state.messages {
+            by_channel
+                .entry(message.channel_id)
+                .or_default()
+                .push((message.date, *key));
+        }
+
+        let mut deleted = 0;
+        for mut messages in by_channel.into_values() {
+            messages.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+
+            for (_, key) in messages.into_iter().skip(max_per_channel as usize) {
+                state.messages.remove(&key);
+                deleted += 1;
+            }
+        }
+
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,67 @@
+mod delivery;
+mod digest;
+mod event;
+mod filter;
+mod message;
+mod subscription;
+mod summarize;
+mod summary;
+mod user;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::TgFeedRepoResult;
+use crate::models::{
+    Delivery, DigestSchedule, FilterRule, QueuedEvent, StoredMessage, StoredSummary, Subscription,
+    User,
+};
+use crate::storage::Storage;
+
+/// In-process storage for tests.
+///
+/// Collections are keyed by the same fields as the unique indexes of the
+/// other backends, so upserts and duplicates behave identically.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Keyed by `(user_id, channel_id)`
+    subscriptions: BTreeMap<(i64, i64), Subscription>,
+    /// Keyed by `(channel_id, message_id)`
+    messages: BTreeMap<(i64, i32), StoredMessage>,
+    /// Keyed by `user_id`
+    summarize_state: HashMap<i64, chrono::DateTime<chrono::Utc>>,
+    /// In insertion order
+    summaries: Vec<StoredSummary>,
+    /// Keyed by `user_id`
+    digest_schedules: BTreeMap<i64, DigestSchedule>,
+    /// In insertion order
+    filters: Vec<FilterRule>,
+    /// Keyed by `(channel_id, message_id, user_id)`
+    deliveries: BTreeMap<(i64, i32, i64), Delivery>,
+    /// Keyed by a sequence number, the event id
+    events: BTreeMap<u64, QueuedEvent>,
+    last_event_id: u64,
+    /// Keyed by `telegram_id`
+    users: BTreeMap<i64, User>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Storage for MemoryStorage {
+    async fn ping(&self) -> TgFeedRepoResult<()> {
+        Ok(())
+    }
+}
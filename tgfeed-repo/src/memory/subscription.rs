@@ -0,0 +1,103 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::Subscription;
+use crate::storage::SubscriptionStore;
+
+impl SubscriptionStore for MemoryStorage {
+    async fn add_subscription(&self, sub: Subscription) -> TgFeedRepoResult<()> {
+        self.state()
+            .subscriptions
+            .insert((sub.user_id, sub.channel_id), sub);
+
+        Ok(())
+    }
+
+    async fn remove_subscription_by_handle(
+        &self,
+        user_id: i64,
+        channel_handle: &str,
+    ) -> TgFeedRepoResult<bool> {
+        let mut state = self.state();
+
+        let key = state
+            .subscriptions
+            .iter()
+            .find(|(_, s)| s.user_id == user_id && s.channel_handle == channel_handle)
+            .map(|(key, _)| *key);
+
+        Ok(key
+            .and_then(|key| state.subscriptions.remove(&key))
+            .is_some())
+    }
+
+    async fn remove_subscription(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
+        Ok(self
+            .state()
+            .subscriptions
+            .remove(&(user_id, channel_id))
+            .is_some())
+    }
+
+    async fn update_subscription_handle(
+        &self,
+        channel_id: i64,
+        new_handle: &str,
+    ) -> TgFeedRepoResult<()> {
+        self.state()
+            .subscriptions
+            .values_mut()
+            .filter(|s| s.channel_id == channel_id)
+            .for_each(|s| s.channel_handle = new_handle.to_string());
+
+        Ok(())
+    }
+
+    async fn get_user_subscriptions(&self, user_id: i64) -> TgFeedRepoResult<Vec<Subscription>> {
+        Ok(self
+            .state()
+            .subscriptions
+            .values()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_channel_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<Vec<i64>> {
+        Ok(self
+            .state()
+            .subscriptions
+            .values()
+            .filter(|s| s.channel_id == channel_id)
+            .map(|s| s.user_id)
+            .collect())
+    }
+
+    async fn is_user_subscribed(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
+        Ok(self
+            .state()
+            .subscriptions
+            .contains_key(&(user_id, channel_id)))
+    }
+
+    async fn has_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
+        Ok(self
+            .state()
+            .subscriptions
+            .values()
+            .any(|s| s.channel_id == channel_id))
+    }
+
+    async fn get_subscribed_channels(&self) -> TgFeedRepoResult<Vec<i64>> {
+        let mut channel_ids = self
+            .state()
+            .subscriptions
+            .values()
+            .map(|s| s.channel_id)
+            .collect::<Vec<_>>();
+
+        channel_ids.sort();
+        channel_ids.dedup();
+
+        Ok(channel_ids)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::storage::SummarizeStore;
+
+impl SummarizeStore for MemoryStorage {
+    async fn get_last_summarize_time(
+        &self,
+        user_id: i64,
+    ) -> TgFeedRepoResult<Option<chrono::DateTime<chrono::Utc>>> {
+        Ok(self.state().summarize_state.get(&user_id).copied())
+    }
+
+    async fn update_summarize_time(
+        &self,
+        user_id: i64,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
+        self.state().summarize_state.insert(user_id, at);
+
+        Ok(())
+    }
+}
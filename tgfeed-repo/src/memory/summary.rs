@@ -0,0 +1,33 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::StoredSummary;
+use crate::storage::SummaryStore;
+
+impl SummaryStore for MemoryStorage {
+    async fn store_summary(&self, summary: StoredSummary) -> TgFeedRepoResult<()> {
+        self.state().summaries.push(summary);
+
+        Ok(())
+    }
+
+    async fn get_user_summaries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredSummary>> {
+        // Reversed first so that equal timestamps keep the latest insert first
+        let mut summaries = self
+            .state()
+            .summaries
+            .iter()
+            .rev()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        summaries.truncate(limit.max(0) as usize);
+
+        Ok(summaries)
+    }
+}
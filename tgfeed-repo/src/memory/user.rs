@@ -0,0 +1,48 @@
+use crate::TgFeedRepoResult;
+use crate::memory::MemoryStorage;
+use crate::models::User;
+use crate::storage::UserStore;
+
+impl UserStore for MemoryStorage {
+    async fn is_user_allowed(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        Ok(self.state().users.get(&user_id).is_some_and(|u| u.allowed))
+    }
+
+    async fn set_user_allowed(&self, user_id: i64, allowed: bool) -> TgFeedRepoResult<()> {
+        self.state()
+            .users
+            .entry(user_id)
+            .and_modify(|u| u.allowed = allowed)
+            .or_insert(User {
+                telegram_id: user_id,
+                allowed,
+                active: true,
+            });
+
+        Ok(())
+    }
+
+    async fn set_user_active(&self, user_id: i64, active: bool) -> TgFeedRepoResult<bool> {
+        match self.state().users.get_mut(&user_id) {
+            Some(user) => {
+                user.active = active;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn get_active_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<i64>> {
+        let state = self.state();
+
+        Ok(user_ids
+            .iter()
+            .copied()
+            .filter(|id| state.users.get(id).is_none_or(|u| u.active))
+            .collect())
+    }
+
+    async fn get_users(&self) -> TgFeedRepoResult<Vec<User>> {
+        Ok(self.state().users.values().cloned().collect())
+    }
+}
@@ -1,6 +1,6 @@
 use mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Subscription {
     pub user_id: i64,
     pub channel_id: i64,
@@ -9,7 +9,7 @@ pub struct Subscription {
     pub subscribed_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct StoredMessage {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<mongodb::bson::oid::ObjectId>,
@@ -20,7 +20,29 @@ pub struct StoredMessage {
     pub date: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+/// Full-text search over stored messages
+#[derive(Clone, Debug)]
+pub struct MessageSearch {
+    /// Free-form text, messages matching more of its words rank higher
+    pub query: String,
+    pub channel_ids: Vec<i64>,
+    /// Posted at or after
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Posted before
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    pub offset: u64,
+    pub limit: i64,
+}
+
+/// Single page of search results
+#[derive(Clone)]
+pub struct SearchPage {
+    pub messages: Vec<StoredMessage>,
+    /// Matches across all pages
+    pub total: u64,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct SummarizeState {
     /// User ID who requested summarization
     pub user_id: i64,
@@ -29,8 +51,114 @@ pub struct SummarizeState {
     pub last_summarized_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+/// Generated summary kept for re-reading
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredSummary {
+    pub user_id: i64,
+    /// Start of the covered time range
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub since: chrono::DateTime<chrono::Utc>,
+    /// End of the covered time range
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub until: chrono::DateTime<chrono::Utc>,
+    /// Handles of the summarized channels
+    pub channels: Vec<String>,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// Telegram HTML as sent to the user
+    pub html: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Recurring automatic digest of a user
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct DigestSchedule {
+    pub user_id: i64,
+    /// Hours between digests; daily at `minute_of_day` when unset
+    pub every_hours: Option<i32>,
+    /// Local delivery time of daily digests, in minutes after midnight
+    pub minute_of_day: i32,
+    pub utc_offset_minutes: i32,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub next_run_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Rule deciding which posts are forwarded to a user
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FilterRule {
+    pub user_id: i64,
+    /// Only channel the rule applies to, every channel when unset
+    pub channel_id: Option<i64>,
+    /// Drop matching posts instead of forwarding only matching ones
+    pub exclude: bool,
+    /// Keyword matched case-insensitively, or a regex when `regex` is set
+    pub pattern: String,
+    pub regex: bool,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Sent,
+    /// Last attempt failed, see `last_error`
+    Failed,
+}
+
+/// Sending of a channel post to a user
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Delivery {
+    pub channel_id: i64,
+    pub message_id: i32,
+    pub user_id: i64,
+    /// Id of the bot's message in the user's chat, once sent
+    pub chat_message_id: Option<i32>,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// Time of the last attempt
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub attempted_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Bot event waiting in the outbound queue
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedEvent {
+    /// Assigned by the backend when enqueued
+    pub id: String,
+    /// Serialized event
+    pub payload: String,
+    /// Failed attempts so far
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// Not handed out before this time
+    pub available_at: chrono::DateTime<chrono::Utc>,
+    /// Failed too often, kept only for inspection
+    pub dead: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct User {
     pub telegram_id: i64,
     pub allowed: bool,
+    /// Cleared when the bot can no longer reach the user, e.g. after being
+    /// blocked, until the user sends /start again
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+}
+
+fn active_by_default() -> bool {
+    true
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct SchemaVersion {
+    pub version: u32,
+    pub description: String,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub applied_at: chrono::DateTime<chrono::Utc>,
 }
@@ -0,0 +1,85 @@
+use mongodb::bson::doc;
+
+use crate::TgFeedRepoResult;
+use crate::models::Delivery;
+use crate::mongo::MongoStorage;
+use crate::storage::DeliveryStore;
+
+impl DeliveryStore for MongoStorage {
+    async fn record_delivery(&self, delivery: Delivery) -> TgFeedRepoResult<()> {
+        self.deliveries()
+            .replace_one(
+                doc! {
+                    "channel_id": delivery.channel_id,
+                    "message_id": delivery.message_id,
+                    "user_id": delivery.user_id,
+                },
+                &delivery,
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_delivery(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        user_id: i64,
+    ) -> TgFeedRepoResult<Option<Delivery>> {
+        Ok(self
+            .deliveries()
+            .find_one(doc! {
+                "channel_id": channel_id,
+                "message_id": message_id,
+                "user_id": user_id,
+            })
+            .await?)
+    }
+
+    async fn get_post_deliveries(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .deliveries()
+            .find(doc! { "channel_id": channel_id, "message_id": message_id })
+            .sort(doc! { "user_id": 1 })
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn get_user_deliveries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .deliveries()
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "attempted_at": -1 })
+            .limit(limit)
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn delete_deliveries_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<u64> {
+        let result = self
+            .deliveries()
+            .delete_many(doc! { "attempted_at": { "$lt": before } })
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+}
@@ -0,0 +1,47 @@
+use mongodb::bson::doc;
+
+use crate::TgFeedRepoResult;
+use crate::models::DigestSchedule;
+use crate::mongo::MongoStorage;
+use crate::storage::DigestStore;
+
+impl DigestStore for MongoStorage {
+    async fn set_digest_schedule(&self, schedule: DigestSchedule) -> TgFeedRepoResult<()> {
+        self.digest_schedules()
+            .replace_one(doc! { "user_id": schedule.user_id }, &schedule)
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        let result = self
+            .digest_schedules()
+            .delete_one(doc! { "user_id": user_id })
+            .await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn get_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<Option<DigestSchedule>> {
+        Ok(self
+            .digest_schedules()
+            .find_one(doc! { "user_id": user_id })
+            .await?)
+    }
+
+    async fn get_due_digest_schedules(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<Vec<DigestSchedule>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .digest_schedules()
+            .find(doc! { "next_run_at": { "$lte": now } })
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+}
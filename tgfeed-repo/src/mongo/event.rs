@@ -0,0 +1,123 @@
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime;
+
+use crate::TgFeedRepoResult;
+use crate::models::QueuedEvent;
+use crate::mongo::MongoStorage;
+use crate::storage::EventQueueStore;
+
+/// Stored form of [`QueuedEvent`], ordered by its object id
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct EventDocument {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    payload: String,
+    attempts: i32,
+    last_error: Option<String>,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    available_at: chrono::DateTime<chrono::Utc>,
+    dead: bool,
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<EventDocument> for QueuedEvent {
+    fn from(event: EventDocument) -> Self {
+        Self {
+            id: event.id.to_hex(),
+            payload: event.payload,
+            attempts: event.attempts,
+            last_error: event.last_error,
+            available_at: event.available_at,
+            dead: event.dead,
+            created_at: event.created_at,
+        }
+    }
+}
+
+impl EventQueueStore for MongoStorage {
+    async fn enqueue_event(&self, payload: String) -> TgFeedRepoResult<()> {
+        let now = chrono::Utc::now();
+
+        self.events()
+            .insert_one(EventDocument {
+                id: ObjectId::new(),
+                payload,
+                attempts: 0,
+                last_error: None,
+                available_at: now,
+                dead: false,
+                created_at: now,
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_due_events(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<QueuedEvent>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .events()
+            .find(doc! { "dead": false, "available_at": { "$lte": now } })
+            .sort(doc! { "_id": 1 })
+            .limit(limit)
+            .await?;
+
+        Ok(cursor.map_ok(QueuedEvent::from).try_collect().await?)
+    }
+
+    async fn ack_event(&self, id: &str) -> TgFeedRepoResult<bool> {
+        let Ok(id) = ObjectId::parse_str(id) else {
+            return Ok(false);
+        };
+
+        let result = self.events().delete_one(doc! { "_id": id }).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn retry_event(
+        &self,
+        id: &str,
+        error: &str,
+        available_at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
+        let Ok(id) = ObjectId::parse_str(id) else {
+            return Ok(());
+        };
+
+        self.events()
+            .update_one(doc! { "_id": id }, doc! {
+                "$inc": { "attempts": 1 },
+                "$set": { "last_error": error, "available_at": available_at },
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn dead_letter_event(&self, id: &str, error: &str) -> TgFeedRepoResult<()> {
+        let Ok(id) = ObjectId::parse_str(id) else {
+            return Ok(());
+        };
+
+        self.events()
+            .update_one(doc! { "_id": id }, doc! {
+                "$inc": { "attempts": 1 },
+                "$set": { "last_error": error, "dead": true },
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn count_events(&self, dead: bool) -> TgFeedRepoResult<u64> {
+        Ok(self.events().count_documents(doc! { "dead": dead }).await?)
+    }
+}
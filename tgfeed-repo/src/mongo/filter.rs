@@ -0,0 +1,59 @@
+use mongodb::bson::{Document, doc};
+
+use crate::TgFeedRepoResult;
+use crate::models::FilterRule;
+use crate::mongo::MongoStorage;
+use crate::storage::FilterStore;
+
+/// Query matching the same rule apart from when it was added
+fn same_rule(rule: &FilterRule) -> Document {
+    doc! {
+        "user_id": rule.user_id,
+        "channel_id": rule.channel_id,
+        "exclude": rule.exclude,
+        "pattern": &rule.pattern,
+        "regex": rule.regex,
+    }
+}
+
+impl FilterStore for MongoStorage {
+    async fn add_filter(&self, rule: FilterRule) -> TgFeedRepoResult<()> {
+        self.filters()
+            .replace_one(same_rule(&rule), &rule)
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_filter(&self, rule: &FilterRule) -> TgFeedRepoResult<bool> {
+        let result = self.filters().delete_one(same_rule(rule)).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn clear_filters(&self, user_id: i64) -> TgFeedRepoResult<u64> {
+        let result = self
+            .filters()
+            .delete_many(doc! { "user_id": user_id })
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn get_user_filters(&self, user_id: i64) -> TgFeedRepoResult<Vec<FilterRule>> {
+        self.get_filters_of_users(&[user_id]).await
+    }
+
+    async fn get_filters_of_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<FilterRule>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .filters()
+            .find(doc! { "user_id": { "$in": user_ids } })
+            .sort(doc! { "created_at": 1 })
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+
+use crate::models::{MessageSearch, SearchPage, StoredMessage};
+use crate::mongo::MongoStorage;
+use crate::storage::MessageStore;
+use crate::{TgFeedRepoResult, search};
+
+impl MessageStore for MongoStorage {
+    async fn store_message(&self, msg: StoredMessage) -> TgFeedRepoResult<()> {
+        // Upsert to avoid duplicates
+        self.messages()
+            .update_one(
+                doc! {
+                    "channel_id": msg.channel_id,
+                    "message_id": msg.message_id
+                },
+                doc! { "$set": mongodb::bson::to_document(&msg)? },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_messages_since(
+        &self,
+        channel_ids: &[i64],
+        since: chrono::DateTime<Utc>,
+        until: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredMessage>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .messages()
+            .find(doc! {
+                "channel_id": { "$in": channel_ids },
+                "date": { "$gte": since, "$lt": until },
+            })
+            .sort(doc! { "date": -1 })
+            .limit(limit)
+            .await?;
+
+        let messages: Vec<StoredMessage> = cursor.try_collect().await?;
+        Ok(messages)
+    }
+
+    async fn search_messages(&self, search: &MessageSearch) -> TgFeedRepoResult<SearchPage> {
+        use futures::TryStreamExt;
+
+        let terms = search::terms(&search.query);
+        if terms.is_empty() {
+            return Ok(SearchPage {
+                messages: Vec::new(),
+                total: 0,
+            });
+        }
+
+        // Passing only the words keeps quotes and dashes in the query from
+        // turning into phrase and negation operators
+        let mut filter = doc! {
+            "$text": { "$search": terms.join(" ") },
+            "channel_id": { "$in": &search.channel_ids },
+        };
+
+        let mut date = doc! {};
+        if let Some(since) = search.since {
+            date.insert("$gte", mongodb::bson::DateTime::from_chrono(since));
+        }
+        if let Some(until) = search.until {
+            date.insert("$lt", mongodb::bson::DateTime::from_chrono(until));
+        }
+        if !date.is_empty() {
+            filter.insert("date", date);
+        }
+
+        let total = self.messages().count_documents(filter.clone()).await?;
+
+        let messages: Vec<StoredMessage> = self
+            .messages()
+            .find(filter)
+            .sort(doc! { "score": { "$meta": "textScore" }, "date": -1 })
+            .skip(search.offset)
+            .limit(search.limit)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(SearchPage { messages, total })
+    }
+
+    async fn update_message_text(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        text: &str,
+    ) -> TgFeedRepoResult<bool> {
+        let result = self
+            .messages()
+            .update_one(
+                doc! { "channel_id": channel_id, "message_id": message_id },
+                doc! { "$set": { "text": text } },
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn delete_messages(&self, channel_id: i64, message_ids: &[i32]) -> TgFeedRepoResult<u64> {
+        let result = self
+            .messages()
+            .delete_many(doc! {
+                "channel_id": channel_id,
+                "message_id": { "$in": message_ids },
+            })
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn delete_messages_before(&self, before: chrono::DateTime<Utc>) -> TgFeedRepoResult<u64> {
+        let result = self
+            .messages()
+            .delete_many(doc! { "date": { "$lt": before } })
+            .await?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn trim_channel_messages(&self, max_per_channel: u64) -> TgFeedRepoResult<u64> {
+        use futures::TryStreamExt;
+        use mongodb::bson::Document;
+
+        let channel_ids = self.messages().distinct("channel_id", doc! {}).await?;
+
+        let mut deleted = 0;
+        for channel_id in channel_ids.into_iter().filter_map(|v| v.as_i64()) {
+            let excess: Vec<Document> = self
+                .messages()
+                .clone_with_type::<Document>()
+                .find(doc! { "channel_id": channel_id })
+                .sort(doc! { "date": -1 })
+                .skip(max_per_channel)
+                .projection(doc! { "_id": 1 })
+                .await?
+                .try_collect()
+                .await?;
+
+            if excess.is_empty() {
+                continue;
+            }
+
+            let ids = excess
+                .into_iter()
+                .filter_map(|d| d.get("_id").cloned())
+                .collect::<Vec<_>>();
+
+            let result = self
+                .messages()
+                .delete_many(doc! { "_id": { "$in": ids } })
+                .await?;
+
+            deleted += result.deleted_count;
+        }
+
+        Ok(deleted)
+    }
+}
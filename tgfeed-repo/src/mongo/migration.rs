@@ -0,0 +1,140 @@
+//! Ordered schema migrations for the Mongo backend.
+//!
+//! Every applied step is recorded in the `schema_version` collection. Steps
+//! must be idempotent: a step interrupted before it was recorded runs again.
+
+use futures::future::BoxFuture;
+use mongodb::bson::doc;
+
+use crate::TgFeedRepoResult;
+use crate::models::SchemaVersion;
+use crate::mongo::MongoStorage;
+
+pub(crate) struct Migration {
+    pub(crate) version: u32,
+    description: &'static str,
+    run: for<'a> fn(&'a MongoStorage) -> BoxFuture<'a, TgFeedRepoResult<()>>,
+}
+
+/// All migrations, ordered by version
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create initial indexes",
+        run: |storage| Box::pin(storage.create_indexes()),
+    },
+    Migration {
+        version: 2,
+        description: "index summaries by user and date",
+        run: |storage| Box::pin(storage.create_summaries_index()),
+    },
+    Migration {
+        version: 3,
+        description: "index digest schedules",
+        run: |storage| Box::pin(storage.create_digest_schedules_indexes()),
+    },
+    Migration {
+        version: 4,
+        description: "full-text index of message text",
+        run: |storage| Box::pin(storage.create_messages_text_index()),
+    },
+    Migration {
+        version: 5,
+        description: "index filters by user",
+        run: |storage| Box::pin(storage.create_filters_index()),
+    },
+    Migration {
+        version: 6,
+        description: "index deliveries by post and user",
+        run: |storage| Box::pin(storage.create_deliveries_indexes()),
+    },
+    Migration {
+        version: 7,
+        description: "index queued bot events",
+        run: |storage| Box::pin(storage.create_events_index()),
+    },
+];
+
+/// State of a single migration step
+pub struct MigrationStatus {
+    pub version: u32,
+    pub description: &'static str,
+    pub applied_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl MongoStorage {
+    /// Latest known schema version
+    pub fn target_schema_version() -> u32 {
+        MIGRATIONS.last().map_or(0, |m| m.version)
+    }
+
+    /// Highest applied schema version, `0` for a fresh database
+    pub async fn schema_version(&self) -> TgFeedRepoResult<u32> {
+        let latest = self
+            .schema_version_collection()
+            .find_one(doc! {})
+            .sort(doc! { "version": -1 })
+            .await?;
+
+        Ok(latest.map_or(0, |v| v.version))
+    }
+
+    pub async fn migration_status(&self) -> TgFeedRepoResult<Vec<MigrationStatus>> {
+        use futures::TryStreamExt;
+
+        let applied: Vec<SchemaVersion> = self
+            .schema_version_collection()
+            .find(doc! {})
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(MIGRATIONS
+            .iter()
+            .map(|m| MigrationStatus {
+                version: m.version,
+                description: m.description,
+                applied_at: applied
+                    .iter()
+                    .find(|a| a.version == m.version)
+                    .map(|a| a.applied_at),
+            })
+            .collect())
+    }
+
+    /// Apply pending migrations in order, returning how many were applied
+    pub async fn migrate(&self) -> TgFeedRepoResult<usize> {
+        let current = self.schema_version().await?;
+
+        let pending = MIGRATIONS.iter().filter(|m| m.version > current);
+
+        let mut applied = 0;
+        for migration in pending {
+            tracing::info!(
+                version = migration.version,
+                description = migration.description,
+                "applying migration"
+            );
+
+            (migration.run)(self).await?;
+
+            let record = SchemaVersion {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied_at: chrono::Utc::now(),
+            };
+
+            self.schema_version_collection()
+                .update_one(
+                    doc! { "version": migration.version },
+                    doc! { "$set": mongodb::bson::to_document(&record)? },
+                )
+                .upsert(true)
+                .await?;
+
+            applied += 1;
+        }
+
+        Ok(applied)
+    }
+}
@@ -0,0 +1,293 @@
+mod delivery;
+mod digest;
+mod event;
+mod filter;
+mod message;
+pub(crate) mod migration;
+mod subscription;
+mod summarize;
+mod summary;
+mod user;
+
+pub use migration::MigrationStatus;
+use mongodb::bson::doc;
+
+use crate::config::MongoConfig;
+use crate::models::{
+    Delivery, DigestSchedule, FilterRule, SchemaVersion, StoredMessage, StoredSummary,
+    Subscription, SummarizeState, User,
+};
+use crate::mongo::event::EventDocument;
+use crate::storage::Storage;
+use crate::{TgFeedRepoError, TgFeedRepoResult};
+
+#[derive(Clone)]
+pub struct MongoStorage {
+    db: mongodb::Database,
+}
+
+impl MongoStorage {
+    pub async fn new(config: &MongoConfig) -> TgFeedRepoResult<Self> {
+        let this = Self::connect(config).await?;
+
+        if config.auto_migrate {
+            this.migrate().await?;
+        } else {
+            let current = this.schema_version().await?;
+            let target = Self::target_schema_version();
+
+            if current < target {
+                return Err(TgFeedRepoError::PendingMigrations { current, target });
+            }
+        }
+
+        Ok(this)
+    }
+
+    /// Connect without checking or applying migrations
+    pub async fn connect(config: &MongoConfig) -> TgFeedRepoResult<Self> {
+        let client_options =
+            mongodb::options::ClientOptions::parse(&config.connection_string).await?;
+
+        let client = mongodb::Client::with_options(client_options)?;
+
+        let db = client.database(&config.database_name);
+
+        Ok(Self { db })
+    }
+
+    async fn create_indexes(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+        use mongodb::options::IndexOptions;
+
+        // Subscriptions indexes
+        self.subscriptions()
+            .create_index(IndexModel::builder().keys(doc! { "user_id": 1 }).build())
+            .await?;
+
+        self.subscriptions()
+            .create_index(IndexModel::builder().keys(doc! { "channel_id": 1 }).build())
+            .await?;
+
+        // Unique constraint: one subscription per user per channel
+        self.subscriptions()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "channel_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        // Messages indexes
+        self.messages()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "channel_id": 1, "date": -1 })
+                    .build(),
+            )
+            .await?;
+
+        // Unique constraint: one message per channel per message_id
+        self.messages()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "channel_id": 1, "message_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        // Summarize state index
+        self.summarize_state()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        // Users index
+        self.users()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "telegram_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        tracing::info!("Database indexes created/verified");
+
+        Ok(())
+    }
+
+    async fn create_summaries_index(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+
+        self.summaries()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "created_at": -1 })
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_digest_schedules_indexes(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+        use mongodb::options::IndexOptions;
+
+        self.digest_schedules()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        self.digest_schedules()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "next_run_at": 1 })
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_messages_text_index(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+        use mongodb::options::IndexOptions;
+
+        // No stemming or stop words, channels post in any language
+        self.messages()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "text": "text" })
+                    .options(
+                        IndexOptions::builder()
+                            .default_language("none".to_string())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_filters_index(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+
+        self.filters()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "created_at": 1 })
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_deliveries_indexes(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+        use mongodb::options::IndexOptions;
+
+        // Unique constraint: one delivery per post per user
+        self.deliveries()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "channel_id": 1, "message_id": 1, "user_id": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        self.deliveries()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "attempted_at": 1 })
+                    .build(),
+            )
+            .await?;
+
+        self.deliveries()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "attempted_at": -1 })
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_events_index(&self) -> TgFeedRepoResult<()> {
+        use mongodb::IndexModel;
+
+        self.events()
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "dead": 1, "available_at": 1 })
+                    .build(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    fn subscriptions(&self) -> mongodb::Collection<Subscription> {
+        self.db.collection("subscriptions")
+    }
+
+    fn messages(&self) -> mongodb::Collection<StoredMessage> {
+        self.db.collection("messages")
+    }
+
+    fn summarize_state(&self) -> mongodb::Collection<SummarizeState> {
+        self.db.collection("summarize_state")
+    }
+
+    fn summaries(&self) -> mongodb::Collection<StoredSummary> {
+        self.db.collection("summaries")
+    }
+
+    fn digest_schedules(&self) -> mongodb::Collection<DigestSchedule> {
+        self.db.collection("digest_schedules")
+    }
+
+    fn filters(&self) -> mongodb::Collection<FilterRule> {
+        self.db.collection("filters")
+    }
+
+    fn deliveries(&self) -> mongodb::Collection<Delivery> {
+        self.db.collection("deliveries")
+    }
+
+    fn events(&self) -> mongodb::Collection<EventDocument> {
+        self.db.collection("events")
+    }
+
+    fn users(&self) -> mongodb::Collection<User> {
+        self.db.collection("users")
+    }
+
+    fn schema_version_collection(&self) -> mongodb::Collection<SchemaVersion> {
+        self.db.collection("schema_version")
+    }
+}
+
+impl Storage for MongoStorage {
+    async fn ping(&self) -> TgFeedRepoResult<()> {
+        self.db.run_command(doc! { "ping": 1 }).await?;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use mongodb::bson::doc;
 
+use crate::TgFeedRepoResult;
 use crate::models::Subscription;
-use crate::{Repo, TgFeedRepoResult};
+use crate::mongo::MongoStorage;
+use crate::storage::SubscriptionStore;
 
-impl Repo {
-    pub async fn add_subscription(&self, sub: Subscription) -> TgFeedRepoResult<()> {
+impl SubscriptionStore for MongoStorage {
+    async fn add_subscription(&self, sub: Subscription) -> TgFeedRepoResult<()> {
         self.subscriptions()
             .update_one(
                 doc! { "user_id": sub.user_id, "channel_id": &sub.channel_id },
@@ -16,7 +18,7 @@ impl Repo {
         Ok(())
     }
 
-    pub async fn remove_subscription_by_handle(
+    async fn remove_subscription_by_handle(
         &self,
         user_id: i64,
         channel_handle: &str,
@@ -29,11 +31,7 @@ impl Repo {
         Ok(result.deleted_count > 0)
     }
 
-    pub async fn remove_subscription(
-        &self,
-        user_id: i64,
-        channel_id: i64,
-    ) -> TgFeedRepoResult<bool> {
+    async fn remove_subscription(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
         let result = self
             .subscriptions()
             .delete_one(doc! { "user_id": user_id, "channel_id": channel_id })
@@ -42,7 +40,7 @@ impl Repo {
         Ok(result.deleted_count > 0)
     }
 
-    pub async fn update_subscription_handle(
+    async fn update_subscription_handle(
         &self,
         channel_id: i64,
         new_handle: &str,
@@ -57,10 +55,7 @@ impl Repo {
         Ok(())
     }
 
-    pub async fn get_user_subscriptions(
-        &self,
-        user_id: i64,
-    ) -> TgFeedRepoResult<Vec<Subscription>> {
+    async fn get_user_subscriptions(&self, user_id: i64) -> TgFeedRepoResult<Vec<Subscription>> {
         use futures::TryStreamExt;
 
         let cursor = self
@@ -72,16 +67,7 @@ impl Repo {
         Ok(subs)
     }
 
-    pub async fn is_subscribed(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
-        let count = self
-            .subscriptions()
-            .count_documents(doc! { "channel_id": channel_id })
-            .await?;
-
-        Ok(count > 0)
-    }
-
-    pub async fn get_channel_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<Vec<i64>> {
+    async fn get_channel_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<Vec<i64>> {
         use futures::TryStreamExt;
 
         let cursor = self
@@ -93,11 +79,7 @@ impl Repo {
         Ok(subs.into_iter().map(|s| s.user_id).collect())
     }
 
-    pub async fn is_user_subscribed(
-        &self,
-        user_id: i64,
-        channel_id: i64,
-    ) -> TgFeedRepoResult<bool> {
+    async fn is_user_subscribed(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
         let count = self
             .subscriptions()
             .count_documents(doc! { "user_id": user_id, "channel_id": channel_id })
@@ -106,7 +88,7 @@ impl Repo {
         Ok(count > 0)
     }
 
-    pub async fn has_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
+    async fn has_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
         let count = self
             .subscriptions()
             .count_documents(doc! { "channel_id": channel_id })
@@ -115,7 +97,7 @@ impl Repo {
         Ok(count > 0)
     }
 
-    pub async fn get_subscribed_channels(&self) -> TgFeedRepoResult<Vec<i64>> {
+    async fn get_subscribed_channels(&self) -> TgFeedRepoResult<Vec<i64>> {
         let cursor = self.subscriptions().distinct("channel_id", doc! {}).await?;
 
         let channel_ids = cursor.into_iter().filter_map(|v| v.as_i64()).collect();
@@ -1,29 +1,31 @@
 use mongodb::bson::doc;
 
+use crate::TgFeedRepoResult;
 use crate::models::SummarizeState;
-use crate::{Repo, TgFeedRepoResult};
+use crate::mongo::MongoStorage;
+use crate::storage::SummarizeStore;
 
-impl Repo {
-    pub async fn get_last_summarize_time(
+impl SummarizeStore for MongoStorage {
+    async fn get_last_summarize_time(
         &self,
         user_id: i64,
-    ) -> TgFeedRepoResult<chrono::DateTime<chrono::Utc>> {
+    ) -> TgFeedRepoResult<Option<chrono::DateTime<chrono::Utc>>> {
         let state = self
             .summarize_state()
             .find_one(doc! { "user_id": user_id })
             .await?;
 
-        match state {
-            Some(s) => Ok(s.last_summarized_at),
-            // Default to 3 days ago if never summarized
-            None => Ok(chrono::Utc::now() - chrono::Duration::days(3)),
-        }
+        Ok(state.map(|s| s.last_summarized_at))
     }
 
-    pub async fn update_summarize_time(&self, user_id: i64) -> TgFeedRepoResult<()> {
+    async fn update_summarize_time(
+        &self,
+        user_id: i64,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
         let state = SummarizeState {
             user_id,
-            last_summarized_at: chrono::Utc::now(),
+            last_summarized_at: at,
         };
 
         self.summarize_state()
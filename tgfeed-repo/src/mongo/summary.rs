@@ -0,0 +1,31 @@
+use mongodb::bson::doc;
+
+use crate::TgFeedRepoResult;
+use crate::models::StoredSummary;
+use crate::mongo::MongoStorage;
+use crate::storage::SummaryStore;
+
+impl SummaryStore for MongoStorage {
+    async fn store_summary(&self, summary: StoredSummary) -> TgFeedRepoResult<()> {
+        self.summaries().insert_one(summary).await?;
+
+        Ok(())
+    }
+
+    async fn get_user_summaries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredSummary>> {
+        use futures::TryStreamExt;
+
+        let cursor = self
+            .summaries()
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "created_at": -1, "_id": -1 })
+            .limit(limit)
+            .await?;
+
+        Ok(cursor.try_collect().await?)
+    }
+}
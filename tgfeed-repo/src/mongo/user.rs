@@ -0,0 +1,67 @@
+use bson::doc;
+
+use crate::TgFeedRepoResult;
+use crate::models::User;
+use crate::mongo::MongoStorage;
+use crate::storage::UserStore;
+
+impl UserStore for MongoStorage {
+    async fn is_user_allowed(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        let count = self
+            .users()
+            .count_documents(doc! { "telegram_id": user_id, "allowed": true })
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    async fn set_user_allowed(&self, user_id: i64, allowed: bool) -> TgFeedRepoResult<()> {
+        self.users()
+            .update_one(
+                doc! { "telegram_id": user_id },
+                doc! { "$set": { "allowed": allowed } },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_user_active(&self, user_id: i64, active: bool) -> TgFeedRepoResult<bool> {
+        let result = self
+            .users()
+            .update_one(
+                doc! { "telegram_id": user_id },
+                doc! { "$set": { "active": active } },
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    async fn get_active_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<i64>> {
+        use futures::TryStreamExt;
+
+        let inactive: Vec<User> = self
+            .users()
+            .find(doc! { "telegram_id": { "$in": user_ids }, "active": false })
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(user_ids
+            .iter()
+            .copied()
+            .filter(|id| !inactive.iter().any(|u| u.telegram_id == *id))
+            .collect())
+    }
+
+    async fn get_users(&self) -> TgFeedRepoResult<Vec<User>> {
+        use futures::TryStreamExt;
+
+        let cursor = self.users().find(doc! {}).await?;
+
+        let users: Vec<User> = cursor.try_collect().await?;
+        Ok(users)
+    }
+}
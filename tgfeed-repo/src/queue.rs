@@ -0,0 +1,111 @@
+//! Durable queue of events from the monitor to the bot.
+//!
+//! Events stay in the repo until the bot acknowledges them, so nothing queued
+//! is lost on restart and a slow bot never holds up the monitor.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::models::QueuedEvent;
+use crate::{Repo, TgFeedRepoResult};
+
+/// Failed attempts before an event is dead-lettered
+pub const MAX_ATTEMPTS: i32 = 5;
+
+/// Delay before the first retry, doubled after every further failure
+const RETRY_DELAY: chrono::TimeDelta = chrono::TimeDelta::seconds(30);
+
+#[derive(Clone)]
+pub struct EventQueue {
+    repo: Repo,
+    signal: Arc<Signal>,
+}
+
+#[derive(Default)]
+struct Signal {
+    /// Woken on push and on close
+    wake: Notify,
+    closed: AtomicBool,
+}
+
+impl EventQueue {
+    pub fn new(repo: Repo) -> Self {
+        Self {
+            repo,
+            signal: Arc::default(),
+        }
+    }
+
+    /// Store an event until it is acknowledged
+    pub async fn push<E: serde::Serialize>(&self, event: &E) -> TgFeedRepoResult<()> {
+        let payload = serde_json::to_string(event).expect("queued events serialize to JSON");
+
+        self.repo.enqueue_event(payload).await?;
+        self.signal.wake.notify_one();
+
+        Ok(())
+    }
+
+    /// Events ready to be handled, oldest first
+    pub async fn due(&self, limit: i64) -> TgFeedRepoResult<Vec<QueuedEvent>> {
+        self.repo.get_due_events(chrono::Utc::now(), limit).await
+    }
+
+    pub fn decode<E: serde::de::DeserializeOwned>(
+        event: &QueuedEvent,
+    ) -> Result<E, serde_json::Error> {
+        serde_json::from_str(&event.payload)
+    }
+
+    pub async fn ack(&self, event: &QueuedEvent) -> TgFeedRepoResult<()> {
+        self.repo.ack_event(&event.id).await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt and retry the event later, returns `true` if
+    /// it failed too often and was dead-lettered instead
+    pub async fn fail(&self, event: &QueuedEvent, error: &str) -> TgFeedRepoResult<bool> {
+        let attempts = event.attempts + 1;
+
+        if attempts >= MAX_ATTEMPTS {
+            self.dead_letter(event, error).await?;
+            return Ok(true);
+        }
+
+        let available_at = chrono::Utc::now() + retry_delay(attempts);
+        self.repo
+            .retry_event(&event.id, error, available_at)
+            .await?;
+
+        Ok(false)
+    }
+
+    /// Give up on an event that can never be handled
+    pub async fn dead_letter(&self, event: &QueuedEvent, error: &str) -> TgFeedRepoResult<()> {
+        self.repo.dead_letter_event(&event.id, error).await
+    }
+
+    /// Wait until an event is pushed or the queue is closed, at most `timeout`
+    pub async fn wait(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.signal.wake.notified()).await;
+    }
+
+    /// Tell the consumer to stop, events left in the queue are kept
+    pub fn close(&self) {
+        self.signal.closed.store(true, Ordering::Release);
+        self.signal.wake.notify_one();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.signal.closed.load(Ordering::Acquire)
+    }
+}
+
+/// Delay before retrying an event that failed `attempts` times
+pub fn retry_delay(attempts: i32) -> chrono::TimeDelta {
+    RETRY_DELAY * 2i32.pow(attempts.clamp(1, MAX_ATTEMPTS) as u32 - 1)
+}
@@ -0,0 +1,40 @@
+use crate::{Repo, RetentionConfig, TgFeedRepoResult};
+
+impl Repo {
+    /// Apply the retention policy once, returning how many messages were deleted.
+    /// Deliveries of posts older than the age limit go too
+    pub async fn prune_messages(&self, config: &RetentionConfig) -> TgFeedRepoResult<u64> {
+        let before = chrono::Utc::now() - chrono::Duration::days(config.max_age_days.into());
+
+        self.delete_deliveries_before(before).await?;
+
+        let mut deleted = self.delete_messages_before(before).await?;
+
+        if let Some(max_per_channel) = config.max_messages_per_channel {
+            deleted += self.trim_channel_messages(max_per_channel).await?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Periodically prune stored messages according to the retention policy
+    pub async fn run_retention(self, config: RetentionConfig) {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(config.prune_interval_secs));
+
+        tracing::info!(
+            max_age_days = config.max_age_days,
+            max_messages_per_channel = ?config.max_messages_per_channel,
+            "Starting message retention task"
+        );
+
+        loop {
+            interval.tick().await;
+
+            match self.prune_messages(&config).await {
+                Ok(deleted) => tracing::info!(deleted, "pruned stored messages"),
+                Err(error) => tracing::error!(%error, "failed pruning stored messages"),
+            }
+        }
+    }
+}
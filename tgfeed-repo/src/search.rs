@@ -0,0 +1,64 @@
+//! Keyword matching of messages against a free-form query, for backends
+//! without a text index
+
+use crate::models::{MessageSearch, SearchPage, StoredMessage};
+
+/// Shorter words are mostly prepositions and articles
+const MIN_TERM_CHARS: usize = 3;
+
+/// Longer queries are cut to their first words
+const MAX_TERMS: usize = 16;
+
+/// Distinct lowercase words of `query` worth matching
+pub(crate) fn terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+
+    for word in query.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+
+        if word.chars().count() >= MIN_TERM_CHARS && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+
+    terms.truncate(MAX_TERMS);
+    terms
+}
+
+/// Whether `message` is in the channels and date range of `search`
+pub(crate) fn in_scope(message: &StoredMessage, search: &MessageSearch) -> bool {
+    search.channel_ids.contains(&message.channel_id)
+        && search.since.is_none_or(|since| message.date >= since)
+        && search.until.is_none_or(|until| message.date < until)
+}
+
+/// Requested page of `messages` containing at least one word of the query,
+/// most matching words first, then newest first
+pub(crate) fn page(
+    messages: impl IntoIterator<Item = StoredMessage>,
+    search: &MessageSearch,
+) -> SearchPage {
+    let terms = terms(&search.query);
+
+    let mut ranked = messages
+        .into_iter()
+        .filter_map(|message| {
+            let text = message.text.to_lowercase();
+            let score = terms.iter().filter(|t| text.contains(t.as_str())).count();
+
+            (score > 0).then_some((score, message))
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|(a_score, a), (b_score, b)| b_score.cmp(a_score).then(b.date.cmp(&a.date)));
+
+    SearchPage {
+        total: ranked.len() as u64,
+        messages: ranked
+            .into_iter()
+            .skip(search.offset as usize)
+            .take(search.limit.max(0) as usize)
+            .map(|(_, message)| message)
+            .collect(),
+    }
+}
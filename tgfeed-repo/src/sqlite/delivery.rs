@@ -0,0 +1,139 @@
+use crate::TgFeedRepoResult;
+use crate::models::{Delivery, DeliveryStatus};
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::DeliveryStore;
+
+const COLUMNS: &str =
+    "channel_id, message_id, user_id, chat_message_id, status, attempts, last_error, attempted_at";
+
+fn status_name(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Sent => "sent",
+        DeliveryStatus::Failed => "failed",
+    }
+}
+
+fn read_delivery(statement: &sqlite::Statement) -> sqlite::Result<Delivery> {
+    let status = match statement.read::<String, _>("status")?.as_str() {
+        "sent" => DeliveryStatus::Sent,
+        _ => DeliveryStatus::Failed,
+    };
+
+    Ok(Delivery {
+        channel_id: statement.read("channel_id")?,
+        message_id: statement.read::<i64, _>("message_id")? as i32,
+        user_id: statement.read("user_id")?,
+        chat_message_id: statement
+            .read::<Option<i64>, _>("chat_message_id")?
+            .map(|id| id as i32),
+        status,
+        attempts: statement.read::<i64, _>("attempts")? as i32,
+        last_error: statement.read("last_error")?,
+        attempted_at: from_timestamp(statement.read("attempted_at")?),
+    })
+}
+
+impl SqliteStorage {
+    async fn select_deliveries(
+        &self,
+        condition: &'static str,
+        params: Vec<i64>,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT {COLUMNS} FROM deliveries WHERE {condition}"
+            ))?;
+
+            for (i, param) in params.iter().enumerate() {
+                statement.bind((i + 1, *param))?;
+            }
+
+            let mut deliveries = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                deliveries.push(read_delivery(&statement)?);
+            }
+
+            Ok(deliveries)
+        })
+        .await
+    }
+}
+
+impl DeliveryStore for SqliteStorage {
+    async fn record_delivery(&self, delivery: Delivery) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "INSERT OR REPLACE INTO deliveries ({COLUMNS}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            ))?;
+
+            statement.bind((1, delivery.channel_id))?;
+            statement.bind((2, i64::from(delivery.message_id)))?;
+            statement.bind((3, delivery.user_id))?;
+            statement.bind((4, delivery.chat_message_id.map(i64::from)))?;
+            statement.bind((5, status_name(delivery.status)))?;
+            statement.bind((6, i64::from(delivery.attempts)))?;
+            statement.bind((7, delivery.last_error.as_deref()))?;
+            statement.bind((8, to_timestamp(delivery.attempted_at)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_delivery(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        user_id: i64,
+    ) -> TgFeedRepoResult<Option<Delivery>> {
+        let deliveries = self
+            .select_deliveries("channel_id = ? AND message_id = ? AND user_id = ?", vec![
+                channel_id,
+                message_id.into(),
+                user_id,
+            ])
+            .await?;
+
+        Ok(deliveries.into_iter().next())
+    }
+
+    async fn get_post_deliveries(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        self.select_deliveries("channel_id = ? AND message_id = ? ORDER BY user_id", vec![
+            channel_id,
+            message_id.into(),
+        ])
+        .await
+    }
+
+    async fn get_user_deliveries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<Delivery>> {
+        self.select_deliveries("user_id = ? ORDER BY attempted_at DESC LIMIT ?", vec![
+            user_id, limit,
+        ])
+        .await
+    }
+
+    async fn delete_deliveries_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<u64> {
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare("DELETE FROM deliveries WHERE attempted_at < ?")?;
+
+            statement.bind((1, to_timestamp(before)))?;
+            statement.next()?;
+
+            Ok(connection.change_count() as u64)
+        })
+        .await
+    }
+}
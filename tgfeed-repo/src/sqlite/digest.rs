@@ -0,0 +1,88 @@
+use crate::TgFeedRepoResult;
+use crate::models::DigestSchedule;
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::DigestStore;
+
+const COLUMNS: &str = "user_id, every_hours, minute_of_day, utc_offset_minutes, next_run_at";
+
+fn read_schedule(statement: &sqlite::Statement) -> sqlite::Result<DigestSchedule> {
+    Ok(DigestSchedule {
+        user_id: statement.read("user_id")?,
+        every_hours: statement
+            .read::<Option<i64>, _>("every_hours")?
+            .map(|h| h as i32),
+        minute_of_day: statement.read::<i64, _>("minute_of_day")? as i32,
+        utc_offset_minutes: statement.read::<i64, _>("utc_offset_minutes")? as i32,
+        next_run_at: from_timestamp(statement.read("next_run_at")?),
+    })
+}
+
+impl DigestStore for SqliteStorage {
+    async fn set_digest_schedule(&self, schedule: DigestSchedule) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "INSERT OR REPLACE INTO digest_schedules ({COLUMNS}) VALUES (?, ?, ?, ?, ?)"
+            ))?;
+
+            statement.bind((1, schedule.user_id))?;
+            statement.bind((2, schedule.every_hours.map(i64::from)))?;
+            statement.bind((3, i64::from(schedule.minute_of_day)))?;
+            statement.bind((4, i64::from(schedule.utc_offset_minutes)))?;
+            statement.bind((5, to_timestamp(schedule.next_run_at)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare("DELETE FROM digest_schedules WHERE user_id = ?")?;
+
+            statement.bind((1, user_id))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn get_digest_schedule(&self, user_id: i64) -> TgFeedRepoResult<Option<DigestSchedule>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT {COLUMNS} FROM digest_schedules WHERE user_id = ?"
+            ))?;
+
+            statement.bind((1, user_id))?;
+
+            match statement.next()? {
+                sqlite::State::Row => Ok(Some(read_schedule(&statement)?)),
+                sqlite::State::Done => Ok(None),
+            }
+        })
+        .await
+    }
+
+    async fn get_due_digest_schedules(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<Vec<DigestSchedule>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT {COLUMNS} FROM digest_schedules WHERE next_run_at <= ?"
+            ))?;
+
+            statement.bind((1, to_timestamp(now)))?;
+
+            let mut schedules = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                schedules.push(read_schedule(&statement)?);
+            }
+
+            Ok(schedules)
+        })
+        .await
+    }
+}
@@ -0,0 +1,138 @@
+use crate::TgFeedRepoResult;
+use crate::models::QueuedEvent;
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::EventQueueStore;
+
+const COLUMNS: &str = "id, payload, attempts, last_error, available_at, dead, created_at";
+
+fn read_event(statement: &sqlite::Statement) -> sqlite::Result<QueuedEvent> {
+    Ok(QueuedEvent {
+        id: statement.read::<i64, _>("id")?.to_string(),
+        payload: statement.read("payload")?,
+        attempts: statement.read::<i64, _>("attempts")? as i32,
+        last_error: statement.read("last_error")?,
+        available_at: from_timestamp(statement.read("available_at")?),
+        dead: statement.read::<i64, _>("dead")? != 0,
+        created_at: from_timestamp(statement.read("created_at")?),
+    })
+}
+
+impl EventQueueStore for SqliteStorage {
+    async fn enqueue_event(&self, payload: String) -> TgFeedRepoResult<()> {
+        let now = to_timestamp(chrono::Utc::now());
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO events (payload, attempts, available_at, dead, created_at)
+                 VALUES (?, 0, ?, 0, ?)",
+            )?;
+
+            statement.bind((1, payload.as_str()))?;
+            statement.bind((2, now))?;
+            statement.bind((3, now))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_due_events(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<QueuedEvent>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT {COLUMNS} FROM events WHERE dead = 0 AND available_at <= ?
+                 ORDER BY id LIMIT ?"
+            ))?;
+
+            statement.bind((1, to_timestamp(now)))?;
+            statement.bind((2, limit))?;
+
+            let mut events = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                events.push(read_event(&statement)?);
+            }
+
+            Ok(events)
+        })
+        .await
+    }
+
+    async fn ack_event(&self, id: &str) -> TgFeedRepoResult<bool> {
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(false);
+        };
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare("DELETE FROM events WHERE id = ?")?;
+
+            statement.bind((1, id))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn retry_event(
+        &self,
+        id: &str,
+        error: &str,
+        available_at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(());
+        };
+        let error = error.to_string();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "UPDATE events SET attempts = attempts + 1, last_error = ?, available_at = ?
+                 WHERE id = ?",
+            )?;
+
+            statement.bind((1, error.as_str()))?;
+            statement.bind((2, to_timestamp(available_at)))?;
+            statement.bind((3, id))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn dead_letter_event(&self, id: &str, error: &str) -> TgFeedRepoResult<()> {
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(());
+        };
+        let error = error.to_string();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "UPDATE events SET attempts = attempts + 1, last_error = ?, dead = 1 WHERE id = ?",
+            )?;
+
+            statement.bind((1, error.as_str()))?;
+            statement.bind((2, id))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn count_events(&self, dead: bool) -> TgFeedRepoResult<u64> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare("SELECT COUNT(*) FROM events WHERE dead = ?")?;
+
+            statement.bind((1, dead as i64))?;
+            statement.next()?;
+
+            Ok(statement.read::<i64, _>(0)? as u64)
+        })
+        .await
+    }
+}
@@ -0,0 +1,110 @@
+use crate::TgFeedRepoResult;
+use crate::models::FilterRule;
+use crate::sqlite::{SqliteStorage, from_timestamp, placeholders, to_timestamp};
+use crate::storage::FilterStore;
+
+const COLUMNS: &str = "user_id, channel_id, exclude, pattern, regex, created_at";
+
+/// Matches the rule bound to the first five parameters; `IS` also matches
+/// a missing channel
+const SAME_RULE: &str =
+    "user_id = ? AND channel_id IS ? AND exclude = ? AND pattern = ? AND regex = ?";
+
+fn bind_rule(statement: &mut sqlite::Statement, rule: &FilterRule) -> sqlite::Result<()> {
+    statement.bind((1, rule.user_id))?;
+    statement.bind((2, rule.channel_id))?;
+    statement.bind((3, rule.exclude as i64))?;
+    statement.bind((4, rule.pattern.as_str()))?;
+    statement.bind((5, rule.regex as i64))?;
+
+    Ok(())
+}
+
+fn read_rule(statement: &sqlite::Statement) -> sqlite::Result<FilterRule> {
+    Ok(FilterRule {
+        user_id: statement.read("user_id")?,
+        channel_id: statement.read("channel_id")?,
+        exclude: statement.read::<i64, _>("exclude")? != 0,
+        pattern: statement.read("pattern")?,
+        regex: statement.read::<i64, _>("regex")? != 0,
+        created_at: from_timestamp(statement.read("created_at")?),
+    })
+}
+
+impl FilterStore for SqliteStorage {
+    async fn add_filter(&self, rule: FilterRule) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare(format!("DELETE FROM filters WHERE {SAME_RULE}"))?;
+            bind_rule(&mut statement, &rule)?;
+            statement.next()?;
+
+            let mut statement = connection.prepare(format!(
+                "INSERT INTO filters ({COLUMNS}) VALUES (?, ?, ?, ?, ?, ?)"
+            ))?;
+            bind_rule(&mut statement, &rule)?;
+            statement.bind((6, to_timestamp(rule.created_at)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_filter(&self, rule: &FilterRule) -> TgFeedRepoResult<bool> {
+        let rule = rule.clone();
+
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare(format!("DELETE FROM filters WHERE {SAME_RULE}"))?;
+            bind_rule(&mut statement, &rule)?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn clear_filters(&self, user_id: i64) -> TgFeedRepoResult<u64> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare("DELETE FROM filters WHERE user_id = ?")?;
+
+            statement.bind((1, user_id))?;
+            statement.next()?;
+
+            Ok(connection.change_count() as u64)
+        })
+        .await
+    }
+
+    async fn get_user_filters(&self, user_id: i64) -> TgFeedRepoResult<Vec<FilterRule>> {
+        self.get_filters_of_users(&[user_id]).await
+    }
+
+    async fn get_filters_of_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<FilterRule>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids = user_ids.to_vec();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT {COLUMNS} FROM filters WHERE user_id IN ({}) ORDER BY id",
+                placeholders(user_ids.len())
+            ))?;
+
+            for (i, user_id) in user_ids.iter().enumerate() {
+                statement.bind((i + 1, *user_id))?;
+            }
+
+            let mut rules = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                rules.push(read_rule(&statement)?);
+            }
+
+            Ok(rules)
+        })
+        .await
+    }
+}
@@ -0,0 +1,214 @@
+use chrono::Utc;
+
+use crate::models::{MessageSearch, SearchPage, StoredMessage};
+use crate::sqlite::{SqliteStorage, from_timestamp, placeholders, to_timestamp};
+use crate::storage::MessageStore;
+use crate::{TgFeedRepoResult, search};
+
+impl MessageStore for SqliteStorage {
+    async fn store_message(&self, msg: StoredMessage) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO messages (channel_id, message_id, text, date) VALUES (?, ?, ?, ?)
+                 ON CONFLICT (channel_id, message_id)
+                 DO UPDATE SET text = excluded.text, date = excluded.date",
+            )?;
+
+            statement.bind((1, msg.channel_id))?;
+            statement.bind((2, msg.message_id as i64))?;
+            statement.bind((3, msg.text.as_str()))?;
+            statement.bind((4, to_timestamp(msg.date)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_messages_since(
+        &self,
+        channel_ids: &[i64],
+        since: chrono::DateTime<Utc>,
+        until: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredMessage>> {
+        if channel_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let channel_ids = channel_ids.to_vec();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT channel_id, message_id, text, date FROM messages
+                 WHERE channel_id IN ({}) AND date >= ? AND date < ?
+                 ORDER BY date DESC LIMIT ?",
+                placeholders(channel_ids.len())
+            ))?;
+
+            for (i, channel_id) in channel_ids.iter().enumerate() {
+                statement.bind((i + 1, *channel_id))?;
+            }
+            statement.bind((channel_ids.len() + 1, to_timestamp(since)))?;
+            statement.bind((channel_ids.len() + 2, to_timestamp(until)))?;
+            statement.bind((channel_ids.len() + 3, limit))?;
+
+            let mut messages = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                messages.push(StoredMessage {
+                    id: None,
+                    channel_id: statement.read("channel_id")?,
+                    message_id: statement.read::<i64, _>("message_id")? as i32,
+                    text: statement.read("text")?,
+                    date: from_timestamp(statement.read("date")?),
+                });
+            }
+
+            Ok(messages)
+        })
+        .await
+    }
+
+    async fn search_messages(&self, search: &MessageSearch) -> TgFeedRepoResult<SearchPage> {
+        let terms = search::terms(&search.query);
+        if search.channel_ids.is_empty() || terms.is_empty() {
+            return Ok(SearchPage {
+                messages: Vec::new(),
+                total: 0,
+            });
+        }
+
+        // Quoting keeps FTS5 syntax out of user words, `*` matches their longer forms
+        let query = terms
+            .iter()
+            .map(|term| format!("\"{term}\"*"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let search = search.clone();
+
+        self.with_connection(move |connection| {
+            let scope = format!(
+                "FROM messages_fts JOIN messages ON messages.id = messages_fts.rowid
+                 WHERE messages_fts MATCH ? AND channel_id IN ({}) AND date >= ? AND date < ?",
+                placeholders(search.channel_ids.len())
+            );
+            let bind_scope = |statement: &mut sqlite::Statement| -> sqlite::Result<usize> {
+                let count = search.channel_ids.len();
+                statement.bind((1, query.as_str()))?;
+                for (i, channel_id) in search.channel_ids.iter().enumerate() {
+                    statement.bind((i + 2, *channel_id))?;
+                }
+                statement.bind((count + 2, search.since.map_or(i64::MIN, to_timestamp)))?;
+                statement.bind((count + 3, search.until.map_or(i64::MAX, to_timestamp)))?;
+                Ok(count + 4)
+            };
+
+            let mut statement = connection.prepare(format!("SELECT COUNT(*) AS total {scope}"))?;
+            bind_scope(&mut statement)?;
+            statement.next()?;
+            let total = statement.read::<i64, _>("total")? as u64;
+
+            let mut statement = connection.prepare(format!(
+                "SELECT channel_id, message_id, messages.text AS text, date {scope}
+                 ORDER BY bm25(messages_fts), date DESC LIMIT ? OFFSET ?"
+            ))?;
+            let next = bind_scope(&mut statement)?;
+            statement.bind((next, search.limit))?;
+            statement.bind((next + 1, search.offset as i64))?;
+
+            let mut messages = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                messages.push(StoredMessage {
+                    id: None,
+                    channel_id: statement.read("channel_id")?,
+                    message_id: statement.read::<i64, _>("message_id")? as i32,
+                    text: statement.read("text")?,
+                    date: from_timestamp(statement.read("date")?),
+                });
+            }
+
+            Ok(SearchPage { messages, total })
+        })
+        .await
+    }
+
+    async fn update_message_text(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        text: &str,
+    ) -> TgFeedRepoResult<bool> {
+        let text = text.to_string();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection
+                .prepare("UPDATE messages SET text = ? WHERE channel_id = ? AND message_id = ?")?;
+
+            statement.bind((1, text.as_str()))?;
+            statement.bind((2, channel_id))?;
+            statement.bind((3, message_id as i64))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn delete_messages(&self, channel_id: i64, message_ids: &[i32]) -> TgFeedRepoResult<u64> {
+        if message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let message_ids = message_ids.to_vec();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "DELETE FROM messages WHERE channel_id = ? AND message_id IN ({})",
+                placeholders(message_ids.len())
+            ))?;
+
+            statement.bind((1, channel_id))?;
+            for (i, message_id) in message_ids.iter().enumerate() {
+                statement.bind((i + 2, *message_id as i64))?;
+            }
+            statement.next()?;
+
+            Ok(connection.change_count() as u64)
+        })
+        .await
+    }
+
+    async fn delete_messages_before(&self, before: chrono::DateTime<Utc>) -> TgFeedRepoResult<u64> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare("DELETE FROM messages WHERE date < ?")?;
+
+            statement.bind((1, to_timestamp(before)))?;
+            statement.next()?;
+
+            Ok(connection.change_count() as u64)
+        })
+        .await
+    }
+
+    async fn trim_channel_messages(&self, max_per_channel: u64) -> TgFeedRepoResult<u64> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "DELETE FROM messages WHERE rowid IN (
+                    SELECT rowid FROM (
+                        SELECT rowid, ROW_NUMBER() OVER (
+                            PARTITION BY channel_id ORDER BY date DESC
+                        ) AS position
+                        FROM messages
+                    )
+                    WHERE position > ?
+                 )",
+            )?;
+
+            statement.bind((1, max_per_channel as i64))?;
+            statement.next()?;
+
+            Ok(connection.change_count() as u64)
+        })
+        .await
+    }
+}
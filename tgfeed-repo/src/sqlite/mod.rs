@@ -0,0 +1,189 @@
+mod delivery;
+mod digest;
+mod event;
+mod filter;
+mod message;
+mod subscription;
+mod summarize;
+mod summary;
+mod user;
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::{DateTime, Utc};
+
+use crate::TgFeedRepoResult;
+use crate::config::SqliteConfig;
+use crate::storage::Storage;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS subscriptions (
+    user_id INTEGER NOT NULL,
+    channel_id INTEGER NOT NULL,
+    channel_handle TEXT NOT NULL,
+    subscribed_at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, channel_id)
+);
+CREATE INDEX IF NOT EXISTS subscriptions_channel_id ON subscriptions (channel_id);
+
+CREATE TABLE IF NOT EXISTS messages (
+    id INTEGER PRIMARY KEY,
+    channel_id INTEGER NOT NULL,
+    message_id INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    date INTEGER NOT NULL,
+    UNIQUE (channel_id, message_id)
+);
+CREATE INDEX IF NOT EXISTS messages_channel_id_date ON messages (channel_id, date DESC);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    text,
+    content = 'messages',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_fts (rowid, text) VALUES (new.id, new.text);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF text ON messages BEGIN
+    INSERT INTO messages_fts (messages_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    INSERT INTO messages_fts (rowid, text) VALUES (new.id, new.text);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+    INSERT INTO messages_fts (messages_fts, rowid, text) VALUES ('delete', old.id, old.text);
+END;
+
+CREATE TABLE IF NOT EXISTS summarize_state (
+    user_id INTEGER PRIMARY KEY,
+    last_summarized_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS summaries (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    since INTEGER NOT NULL,
+    until INTEGER NOT NULL,
+    channels TEXT NOT NULL,
+    model TEXT NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    html TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS summaries_user_id_created_at ON summaries (user_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS digest_schedules (
+    user_id INTEGER PRIMARY KEY,
+    every_hours INTEGER,
+    minute_of_day INTEGER NOT NULL,
+    utc_offset_minutes INTEGER NOT NULL,
+    next_run_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS digest_schedules_next_run_at ON digest_schedules (next_run_at);
+
+CREATE TABLE IF NOT EXISTS filters (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    channel_id INTEGER,
+    exclude INTEGER NOT NULL,
+    pattern TEXT NOT NULL,
+    regex INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS filters_user_id ON filters (user_id);
+
+CREATE TABLE IF NOT EXISTS deliveries (
+    channel_id INTEGER NOT NULL,
+    message_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    chat_message_id INTEGER,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT,
+    attempted_at INTEGER NOT NULL,
+    PRIMARY KEY (channel_id, message_id, user_id)
+);
+CREATE INDEX IF NOT EXISTS deliveries_attempted_at ON deliveries (attempted_at);
+CREATE INDEX IF NOT EXISTS deliveries_user_id_attempted_at ON deliveries (user_id, attempted_at DESC);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT,
+    available_at INTEGER NOT NULL,
+    dead INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_dead_available_at ON events (dead, available_at);
+
+CREATE TABLE IF NOT EXISTS users (
+    telegram_id INTEGER PRIMARY KEY,
+    allowed INTEGER NOT NULL,
+    active INTEGER NOT NULL DEFAULT 1
+);
+"#;
+
+/// Embedded storage for small deployments and tests.
+///
+/// `sqlite` is blocking, so every query runs on the blocking thread pool
+/// behind a single connection.
+#[derive(Clone)]
+pub struct SqliteStorage {
+    connection: Arc<Mutex<sqlite::ConnectionThreadSafe>>,
+}
+
+impl SqliteStorage {
+    pub async fn open(config: &SqliteConfig) -> TgFeedRepoResult<Self> {
+        let path = config.path.clone();
+
+        let connection = tokio::task::spawn_blocking(move || {
+            let connection = sqlite::Connection::open_thread_safe(path)?;
+            connection.execute(SCHEMA)?;
+            Ok::<_, sqlite::Error>(connection)
+        })
+        .await??;
+
+        tracing::info!("Database schema created/verified");
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    async fn with_connection<T, F>(&self, f: F) -> TgFeedRepoResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&sqlite::Connection) -> sqlite::Result<T> + Send + 'static,
+    {
+        let connection = Arc::clone(&self.connection);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&connection)
+        })
+        .await??;
+
+        Ok(result)
+    }
+}
+
+impl Storage for SqliteStorage {
+    async fn ping(&self) -> TgFeedRepoResult<()> {
+        self.with_connection(|connection| connection.execute("SELECT 1"))
+            .await
+    }
+}
+
+fn to_timestamp(date: DateTime<Utc>) -> i64 {
+    date.timestamp_millis()
+}
+
+fn from_timestamp(millis: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+/// `?, ?, ...` placeholder list for an `IN` clause
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
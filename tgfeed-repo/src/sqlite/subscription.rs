@@ -0,0 +1,174 @@
+use crate::TgFeedRepoResult;
+use crate::models::Subscription;
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::SubscriptionStore;
+
+fn read_subscription(statement: &sqlite::Statement) -> sqlite::Result<Subscription> {
+    Ok(Subscription {
+        user_id: statement.read("user_id")?,
+        channel_id: statement.read("channel_id")?,
+        channel_handle: statement.read("channel_handle")?,
+        subscribed_at: from_timestamp(statement.read("subscribed_at")?),
+    })
+}
+
+impl SubscriptionStore for SqliteStorage {
+    async fn add_subscription(&self, sub: Subscription) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO subscriptions (user_id, channel_id, channel_handle, subscribed_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT (user_id, channel_id)
+                 DO UPDATE SET channel_handle = excluded.channel_handle,
+                               subscribed_at = excluded.subscribed_at",
+            )?;
+
+            statement.bind((1, sub.user_id))?;
+            statement.bind((2, sub.channel_id))?;
+            statement.bind((3, sub.channel_handle.as_str()))?;
+            statement.bind((4, to_timestamp(sub.subscribed_at)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_subscription_by_handle(
+        &self,
+        user_id: i64,
+        channel_handle: &str,
+    ) -> TgFeedRepoResult<bool> {
+        let channel_handle = channel_handle.to_string();
+
+        self.with_connection(move |connection| {
+            // mirror Mongo `delete_one`: remove at most one row
+            let mut statement = connection.prepare(
+                "DELETE FROM subscriptions WHERE rowid IN (
+                    SELECT rowid FROM subscriptions
+                    WHERE user_id = ? AND channel_handle = ? LIMIT 1
+                 )",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, channel_handle.as_str()))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn remove_subscription(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement = connection
+                .prepare("DELETE FROM subscriptions WHERE user_id = ? AND channel_id = ?")?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, channel_id))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn update_subscription_handle(
+        &self,
+        channel_id: i64,
+        new_handle: &str,
+    ) -> TgFeedRepoResult<()> {
+        let new_handle = new_handle.to_string();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection
+                .prepare("UPDATE subscriptions SET channel_handle = ? WHERE channel_id = ?")?;
+
+            statement.bind((1, new_handle.as_str()))?;
+            statement.bind((2, channel_id))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_user_subscriptions(&self, user_id: i64) -> TgFeedRepoResult<Vec<Subscription>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "SELECT user_id, channel_id, channel_handle, subscribed_at
+                 FROM subscriptions WHERE user_id = ?",
+            )?;
+
+            statement.bind((1, user_id))?;
+
+            let mut subs = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                subs.push(read_subscription(&statement)?);
+            }
+
+            Ok(subs)
+        })
+        .await
+    }
+
+    async fn get_channel_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<Vec<i64>> {
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare("SELECT user_id FROM subscriptions WHERE channel_id = ?")?;
+
+            statement.bind((1, channel_id))?;
+
+            let mut user_ids = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                user_ids.push(statement.read("user_id")?);
+            }
+
+            Ok(user_ids)
+        })
+        .await
+    }
+
+    async fn is_user_subscribed(&self, user_id: i64, channel_id: i64) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "SELECT EXISTS (SELECT 1 FROM subscriptions WHERE user_id = ? AND channel_id = ?)",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, channel_id))?;
+            statement.next()?;
+
+            Ok(statement.read::<i64, _>(0)? != 0)
+        })
+        .await
+    }
+
+    async fn has_subscribers(&self, channel_id: i64) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement = connection
+                .prepare("SELECT EXISTS (SELECT 1 FROM subscriptions WHERE channel_id = ?)")?;
+
+            statement.bind((1, channel_id))?;
+            statement.next()?;
+
+            Ok(statement.read::<i64, _>(0)? != 0)
+        })
+        .await
+    }
+
+    async fn get_subscribed_channels(&self) -> TgFeedRepoResult<Vec<i64>> {
+        self.with_connection(|connection| {
+            let mut statement =
+                connection.prepare("SELECT DISTINCT channel_id FROM subscriptions")?;
+
+            let mut channel_ids = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                channel_ids.push(statement.read("channel_id")?);
+            }
+
+            Ok(channel_ids)
+        })
+        .await
+    }
+}
@@ -0,0 +1,45 @@
+use crate::TgFeedRepoResult;
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::SummarizeStore;
+
+impl SummarizeStore for SqliteStorage {
+    async fn get_last_summarize_time(
+        &self,
+        user_id: i64,
+    ) -> TgFeedRepoResult<Option<chrono::DateTime<chrono::Utc>>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection
+                .prepare("SELECT last_summarized_at FROM summarize_state WHERE user_id = ?")?;
+
+            statement.bind((1, user_id))?;
+
+            match statement.next()? {
+                sqlite::State::Row => Ok(Some(from_timestamp(statement.read(0)?))),
+                sqlite::State::Done => Ok(None),
+            }
+        })
+        .await
+    }
+
+    async fn update_summarize_time(
+        &self,
+        user_id: i64,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> TgFeedRepoResult<()> {
+        let at = to_timestamp(at);
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO summarize_state (user_id, last_summarized_at) VALUES (?, ?)
+                 ON CONFLICT (user_id) DO UPDATE SET last_summarized_at = excluded.last_summarized_at",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, at))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+}
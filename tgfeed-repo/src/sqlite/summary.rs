@@ -0,0 +1,74 @@
+use crate::TgFeedRepoResult;
+use crate::models::StoredSummary;
+use crate::sqlite::{SqliteStorage, from_timestamp, to_timestamp};
+use crate::storage::SummaryStore;
+
+/// Channel handles never contain whitespace
+const CHANNEL_SEPARATOR: &str = " ";
+
+impl SummaryStore for SqliteStorage {
+    async fn store_summary(&self, summary: StoredSummary) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO summaries
+                 (user_id, since, until, channels, model, input_tokens, output_tokens, html, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+
+            statement.bind((1, summary.user_id))?;
+            statement.bind((2, to_timestamp(summary.since)))?;
+            statement.bind((3, to_timestamp(summary.until)))?;
+            statement.bind((4, summary.channels.join(CHANNEL_SEPARATOR).as_str()))?;
+            statement.bind((5, summary.model.as_str()))?;
+            statement.bind((6, summary.input_tokens))?;
+            statement.bind((7, summary.output_tokens))?;
+            statement.bind((8, summary.html.as_str()))?;
+            statement.bind((9, to_timestamp(summary.created_at)))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_user_summaries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> TgFeedRepoResult<Vec<StoredSummary>> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "SELECT user_id, since, until, channels, model, input_tokens, output_tokens, html, created_at
+                 FROM summaries WHERE user_id = ?
+                 ORDER BY created_at DESC, id DESC LIMIT ?",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, limit))?;
+
+            let mut summaries = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                let channels: String = statement.read("channels")?;
+
+                summaries.push(StoredSummary {
+                    user_id: statement.read("user_id")?,
+                    since: from_timestamp(statement.read("since")?),
+                    until: from_timestamp(statement.read("until")?),
+                    channels: channels
+                        .split(CHANNEL_SEPARATOR)
+                        .filter(|c| !c.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    model: statement.read("model")?,
+                    input_tokens: statement.read("input_tokens")?,
+                    output_tokens: statement.read("output_tokens")?,
+                    html: statement.read("html")?,
+                    created_at: from_timestamp(statement.read("created_at")?),
+                });
+            }
+
+            Ok(summaries)
+        })
+        .await
+    }
+}
@@ -0,0 +1,99 @@
+use crate::TgFeedRepoResult;
+use crate::models::User;
+use crate::sqlite::{SqliteStorage, placeholders};
+use crate::storage::UserStore;
+
+impl UserStore for SqliteStorage {
+    async fn is_user_allowed(&self, user_id: i64) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "SELECT EXISTS (SELECT 1 FROM users WHERE telegram_id = ? AND allowed = 1)",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.next()?;
+
+            Ok(statement.read::<i64, _>(0)? != 0)
+        })
+        .await
+    }
+
+    async fn set_user_allowed(&self, user_id: i64, allowed: bool) -> TgFeedRepoResult<()> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(
+                "INSERT INTO users (telegram_id, allowed) VALUES (?, ?)
+                 ON CONFLICT (telegram_id) DO UPDATE SET allowed = excluded.allowed",
+            )?;
+
+            statement.bind((1, user_id))?;
+            statement.bind((2, allowed as i64))?;
+            statement.next()?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn set_user_active(&self, user_id: i64, active: bool) -> TgFeedRepoResult<bool> {
+        self.with_connection(move |connection| {
+            let mut statement =
+                connection.prepare("UPDATE users SET active = ? WHERE telegram_id = ?")?;
+
+            statement.bind((1, active as i64))?;
+            statement.bind((2, user_id))?;
+            statement.next()?;
+
+            Ok(connection.change_count() > 0)
+        })
+        .await
+    }
+
+    async fn get_active_users(&self, user_ids: &[i64]) -> TgFeedRepoResult<Vec<i64>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids = user_ids.to_vec();
+
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare(format!(
+                "SELECT telegram_id FROM users WHERE active = 0 AND telegram_id IN ({})",
+                placeholders(user_ids.len())
+            ))?;
+
+            for (i, user_id) in user_ids.iter().enumerate() {
+                statement.bind((i + 1, *user_id))?;
+            }
+
+            let mut inactive = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                inactive.push(statement.read::<i64, _>("telegram_id")?);
+            }
+
+            Ok(user_ids
+                .into_iter()
+                .filter(|id| !inactive.contains(id))
+                .collect())
+        })
+        .await
+    }
+
+    async fn get_users(&self) -> TgFeedRepoResult<Vec<User>> {
+        self.with_connection(|connection| {
+            let mut statement =
+                connection.prepare("SELECT telegram_id, allowed, active FROM users")?;
+
+            let mut users = Vec::new();
+            while let sqlite::State::Row = statement.next()? {
+                users.push(User {
+                    telegram_id: statement.read("telegram_id")?,
+                    allowed: statement.read::<i64, _>("allowed")? != 0,
+                    active: statement.read::<i64, _>("active")? != 0,
+                });
+            }
+
+            Ok(users)
+        })
+        .await
+    }
+}
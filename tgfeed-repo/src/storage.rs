@@ -0,0 +1,302 @@
+//! Storage traits implemented by every backend.
+//!
+//! Operations are split by domain so each backend can keep one file per
+//! collection; [`Storage`] ties them together.
+
+use chrono::{DateTime, Utc};
+
+use crate::TgFeedRepoResult;
+use crate::models::{
+    Delivery, DigestSchedule, FilterRule, MessageSearch, QueuedEvent, SearchPage, StoredMessage,
+    StoredSummary, Subscription, User,
+};
+
+pub trait MessageStore {
+    /// Insert or replace a message, unique by `(channel_id, message_id)`
+    fn store_message(
+        &self,
+        msg: StoredMessage,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Messages of the given channels posted at or after `since` and before
+    /// `until`, newest first
+    fn get_messages_since(
+        &self,
+        channel_ids: &[i64],
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<StoredMessage>>> + Send;
+
+    /// Messages matching `search`, most relevant first, then newest
+    fn search_messages(
+        &self,
+        search: &MessageSearch,
+    ) -> impl Future<Output = TgFeedRepoResult<SearchPage>> + Send;
+
+    /// Replace the text of a stored message, `false` if it isn't stored
+    fn update_message_text(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        text: &str,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    /// Delete the given messages of a channel, returning how many were deleted
+    fn delete_messages(
+        &self,
+        channel_id: i64,
+        message_ids: &[i32],
+    ) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+
+    /// Delete messages posted before `before`, returning how many were deleted
+    fn delete_messages_before(
+        &self,
+        before: DateTime<Utc>,
+    ) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+
+    /// Keep only the newest `max_per_channel` messages of every channel,
+    /// returning how many were deleted
+    fn trim_channel_messages(
+        &self,
+        max_per_channel: u64,
+    ) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+}
+
+pub trait SubscriptionStore {
+    /// Insert or replace a subscription, unique by `(user_id, channel_id)`
+    fn add_subscription(
+        &self,
+        sub: Subscription,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    fn remove_subscription_by_handle(
+        &self,
+        user_id: i64,
+        channel_handle: &str,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    fn remove_subscription(
+        &self,
+        user_id: i64,
+        channel_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    fn update_subscription_handle(
+        &self,
+        channel_id: i64,
+        new_handle: &str,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    fn get_user_subscriptions(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<Subscription>>> + Send;
+
+    fn get_channel_subscribers(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<i64>>> + Send;
+
+    fn is_user_subscribed(
+        &self,
+        user_id: i64,
+        channel_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    fn has_subscribers(
+        &self,
+        channel_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    fn get_subscribed_channels(&self) -> impl Future<Output = TgFeedRepoResult<Vec<i64>>> + Send;
+}
+
+pub trait SummarizeStore {
+    fn get_last_summarize_time(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Option<DateTime<Utc>>>> + Send;
+
+    /// Move the start of the next summary window to `at`
+    fn update_summarize_time(
+        &self,
+        user_id: i64,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+}
+
+pub trait SummaryStore {
+    fn store_summary(
+        &self,
+        summary: StoredSummary,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Summaries generated for `user_id`, newest first
+    fn get_user_summaries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<StoredSummary>>> + Send;
+}
+
+pub trait DigestStore {
+    /// Insert or replace a schedule, unique by `user_id`
+    fn set_digest_schedule(
+        &self,
+        schedule: DigestSchedule,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Returns `true` if the user had a schedule
+    fn remove_digest_schedule(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    fn get_digest_schedule(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Option<DigestSchedule>>> + Send;
+
+    /// Schedules whose `next_run_at` is at or before `now`
+    fn get_due_digest_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<DigestSchedule>>> + Send;
+}
+
+pub trait FilterStore {
+    /// Add a rule, replacing an identical one
+    fn add_filter(&self, rule: FilterRule) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Remove the rule identical to `rule` apart from `created_at`
+    fn remove_filter(
+        &self,
+        rule: &FilterRule,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    /// Remove every rule of the user, returning how many were removed
+    fn clear_filters(&self, user_id: i64) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+
+    /// Rules of the user, oldest first
+    fn get_user_filters(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<FilterRule>>> + Send;
+
+    /// Rules of any of the users, oldest first
+    fn get_filters_of_users(
+        &self,
+        user_ids: &[i64],
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<FilterRule>>> + Send;
+}
+
+pub trait DeliveryStore {
+    /// Insert or replace a delivery, unique by `(channel_id, message_id, user_id)`
+    fn record_delivery(
+        &self,
+        delivery: Delivery,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    fn get_delivery(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+        user_id: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Option<Delivery>>> + Send;
+
+    /// Deliveries of a channel post to every user it was sent to
+    fn get_post_deliveries(
+        &self,
+        channel_id: i64,
+        message_id: i32,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<Delivery>>> + Send;
+
+    /// Deliveries to `user_id`, last attempted first
+    fn get_user_deliveries(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<Delivery>>> + Send;
+
+    /// Delete deliveries last attempted before `before`, returning how many
+    /// were deleted
+    fn delete_deliveries_before(
+        &self,
+        before: DateTime<Utc>,
+    ) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+}
+
+pub trait EventQueueStore {
+    /// Append an event, available right away
+    fn enqueue_event(&self, payload: String) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Live events available at or before `now`, oldest enqueued first
+    fn get_due_events(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<QueuedEvent>>> + Send;
+
+    /// Remove a handled event, `false` if it isn't queued
+    fn ack_event(&self, id: &str) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    /// Count a failed attempt and hold the event back until `available_at`
+    fn retry_event(
+        &self,
+        id: &str,
+        error: &str,
+        available_at: DateTime<Utc>,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Count a failed attempt and never hand the event out again
+    fn dead_letter_event(
+        &self,
+        id: &str,
+        error: &str,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Number of queued events, dead-lettered or live
+    fn count_events(&self, dead: bool) -> impl Future<Output = TgFeedRepoResult<u64>> + Send;
+}
+
+pub trait UserStore {
+    fn is_user_allowed(&self, user_id: i64) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    /// Insert or update a user, unique by `telegram_id`
+    fn set_user_allowed(
+        &self,
+        user_id: i64,
+        allowed: bool,
+    ) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+
+    /// Returns `false` if the user isn't known
+    fn set_user_active(
+        &self,
+        user_id: i64,
+        active: bool,
+    ) -> impl Future<Output = TgFeedRepoResult<bool>> + Send;
+
+    /// The given users that aren't marked inactive, unknown users included
+    fn get_active_users(
+        &self,
+        user_ids: &[i64],
+    ) -> impl Future<Output = TgFeedRepoResult<Vec<i64>>> + Send;
+
+    fn get_users(&self) -> impl Future<Output = TgFeedRepoResult<Vec<User>>> + Send;
+}
+
+pub trait Storage:
+    MessageStore
+    + SubscriptionStore
+    + SummarizeStore
+    + SummaryStore
+    + DigestStore
+    + FilterStore
+    + DeliveryStore
+    + EventQueueStore
+    + UserStore
+{
+    /// Check that the backend is reachable
+    fn ping(&self) -> impl Future<Output = TgFeedRepoResult<()>> + Send;
+}
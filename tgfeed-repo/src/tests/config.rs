@@ -0,0 +1,15 @@
+use serde::de::IntoDeserializer;
+use serde::de::value::Error;
+
+use crate::config::positive_interval;
+
+fn interval(secs: u64) -> Result<u64, Error> {
+    positive_interval(secs.into_deserializer())
+}
+
+#[test]
+fn test_zero_prune_interval_rejected() {
+    assert!(interval(0).is_err());
+    assert_eq!(interval(1).unwrap(), 1);
+    assert_eq!(interval(3600).unwrap(), 3600);
+}
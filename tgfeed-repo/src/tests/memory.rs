@@ -0,0 +1,81 @@
+use crate::Repo;
+use crate::tests::suite;
+
+fn repo() -> Repo {
+    Repo::in_memory()
+}
+
+#[tokio::test]
+async fn test_memory_subscription_unique_per_user_and_channel() {
+    suite::subscription_unique_per_user_and_channel(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_remove_subscription() {
+    suite::remove_subscription(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_messages_since() {
+    suite::messages_since(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_messages_limit() {
+    suite::messages_limit(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_update_subscription_handle() {
+    suite::update_subscription_handle(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_summarize_time_and_users() {
+    suite::summarize_time_and_users(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_inactive_users() {
+    suite::inactive_users(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_prune_messages() {
+    suite::prune_messages(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_summary_history() {
+    suite::summary_history(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_digest_schedules() {
+    suite::digest_schedules(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_search_messages() {
+    suite::search_messages(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_filters() {
+    suite::filters(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_edit_and_delete_messages() {
+    suite::edit_and_delete_messages(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_deliveries() {
+    suite::deliveries(repo()).await;
+}
+
+#[tokio::test]
+async fn test_memory_event_queue() {
+    suite::event_queue(repo()).await;
+}
@@ -0,0 +1,20 @@
+use crate::mongo::MongoStorage;
+use crate::mongo::migration::MIGRATIONS;
+
+#[test]
+fn test_migrations_strictly_ordered() {
+    assert!(!MIGRATIONS.is_empty());
+    assert_eq!(MIGRATIONS[0].version, 1);
+
+    for pair in MIGRATIONS.windows(2) {
+        assert!(pair[0].version < pair[1].version);
+    }
+}
+
+#[test]
+fn test_target_schema_version_is_latest() {
+    assert_eq!(
+        MongoStorage::target_schema_version(),
+        MIGRATIONS.last().unwrap().version
+    );
+}
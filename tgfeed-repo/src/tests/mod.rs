@@ -0,0 +1,6 @@
+mod config;
+mod memory;
+mod migration;
+mod queue;
+mod sqlite;
+mod suite;
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use crate::Repo;
+use crate::models::QueuedEvent;
+use crate::queue::{EventQueue, MAX_ATTEMPTS, retry_delay};
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Event {
+    Digest {
+        user_id: i64,
+        summary: String,
+    },
+    PostsDeleted {
+        channel_id: i64,
+        message_ids: Vec<i32>,
+    },
+}
+
+fn digest(user_id: i64) -> Event {
+    Event::Digest {
+        user_id,
+        summary: "<b>News</b>".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_pushed_events_round_trip() {
+    let queue = EventQueue::new(Repo::in_memory());
+
+    queue.push(&digest(1)).await.unwrap();
+    queue
+        .push(&Event::PostsDeleted {
+            channel_id: 100,
+            message_ids: vec![5, 6],
+        })
+        .await
+        .unwrap();
+
+    let due = queue.due(10).await.unwrap();
+    assert_eq!(due.len(), 2);
+
+    assert_eq!(EventQueue::decode::<Event>(&due[0]).unwrap(), digest(1));
+    assert_eq!(
+        EventQueue::decode::<Event>(&due[1]).unwrap(),
+        Event::PostsDeleted {
+            channel_id: 100,
+            message_ids: vec![5, 6],
+        }
+    );
+
+    queue.ack(&due[0]).await.unwrap();
+    assert_eq!(queue.due(10).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_events_are_retried_then_dead_lettered() {
+    let repo = Repo::in_memory();
+    let queue = EventQueue::new(repo.clone());
+
+    queue.push(&digest(1)).await.unwrap();
+    let event = queue.due(1).await.unwrap().remove(0);
+
+    assert!(!queue.fail(&event, "timed out").await.unwrap());
+    assert!(queue.due(10).await.unwrap().is_empty());
+
+    let last_attempt = QueuedEvent {
+        attempts: MAX_ATTEMPTS - 1,
+        ..event
+    };
+    assert!(queue.fail(&last_attempt, "timed out").await.unwrap());
+    assert_eq!(repo.count_events(true).await.unwrap(), 1);
+}
+
+#[test]
+fn test_retry_delay_doubles() {
+    assert_eq!(retry_delay(1), chrono::TimeDelta::seconds(30));
+    assert_eq!(retry_delay(2), chrono::TimeDelta::seconds(60));
+    assert_eq!(retry_delay(4), chrono::TimeDelta::seconds(240));
+    assert_eq!(retry_delay(100), retry_delay(MAX_ATTEMPTS));
+}
+
+#[tokio::test]
+async fn test_close_wakes_waiting_consumer() {
+    let queue = EventQueue::new(Repo::in_memory());
+    assert!(!queue.is_closed());
+
+    queue.close();
+
+    tokio::time::timeout(Duration::from_secs(1), queue.wait(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    assert!(queue.is_closed());
+}
@@ -0,0 +1,85 @@
+use crate::tests::suite;
+use crate::{Config, Repo, SqliteConfig};
+
+async fn repo() -> Repo {
+    Repo::new(&Config::Sqlite(SqliteConfig {
+        path: ":memory:".into(),
+    }))
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_sqlite_subscription_unique_per_user_and_channel() {
+    suite::subscription_unique_per_user_and_channel(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_remove_subscription() {
+    suite::remove_subscription(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_messages_since() {
+    suite::messages_since(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_messages_limit() {
+    suite::messages_limit(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_update_subscription_handle() {
+    suite::update_subscription_handle(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_summarize_time_and_users() {
+    suite::summarize_time_and_users(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_inactive_users() {
+    suite::inactive_users(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_prune_messages() {
+    suite::prune_messages(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_summary_history() {
+    suite::summary_history(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_digest_schedules() {
+    suite::digest_schedules(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_search_messages() {
+    suite::search_messages(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_filters() {
+    suite::filters(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_edit_and_delete_messages() {
+    suite::edit_and_delete_messages(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_deliveries() {
+    suite::deliveries(repo().await).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_event_queue() {
+    suite::event_queue(repo().await).await;
+}
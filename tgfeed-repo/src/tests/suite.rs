@@ -0,0 +1,605 @@
+//! Behaviour every storage backend must share, run against each of them.
+
+use chrono::{Duration, Utc};
+
+use crate::models::{
+    Delivery, DeliveryStatus, DigestSchedule, FilterRule, MessageSearch, QueuedEvent, SearchPage,
+    StoredMessage, StoredSummary, Subscription,
+};
+use crate::{Repo, RetentionConfig};
+
+fn subscription(user_id: i64, channel_id: i64, channel_handle: &str) -> Subscription {
+    Subscription {
+        user_id,
+        channel_id,
+        channel_handle: channel_handle.to_string(),
+        subscribed_at: Utc::now(),
+    }
+}
+
+fn message(channel_id: i64, message_id: i32, text: &str, age: Duration) -> StoredMessage {
+    StoredMessage {
+        id: None,
+        channel_id,
+        message_id,
+        text: text.to_string(),
+        date: Utc::now() - age,
+    }
+}
+
+pub(super) async fn subscription_unique_per_user_and_channel(repo: Repo) {
+    repo.add_subscription(subscription(1, 100, "old"))
+        .await
+        .unwrap();
+    repo.add_subscription(subscription(1, 100, "new"))
+        .await
+        .unwrap();
+    repo.add_subscription(subscription(2, 100, "new"))
+        .await
+        .unwrap();
+
+    let subs = repo.get_user_subscriptions(1).await.unwrap();
+    assert_eq!(subs.len(), 1);
+    assert_eq!(subs[0].channel_handle, "new");
+
+    let mut subscribers = repo.get_channel_subscribers(100).await.unwrap();
+    subscribers.sort();
+    assert_eq!(subscribers, vec![1, 2]);
+    assert_eq!(repo.get_subscribed_channels().await.unwrap(), vec![100]);
+}
+
+pub(super) async fn remove_subscription(repo: Repo) {
+    repo.add_subscription(subscription(1, 100, "first"))
+        .await
+        .unwrap();
+    repo.add_subscription(subscription(1, 200, "second"))
+        .await
+        .unwrap();
+
+    assert!(
+        repo.remove_subscription_by_handle(1, "first")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repo
+            .remove_subscription_by_handle(1, "first")
+            .await
+            .unwrap()
+    );
+    assert!(repo.remove_subscription(1, 200).await.unwrap());
+    assert!(!repo.is_user_subscribed(1, 200).await.unwrap());
+    assert!(!repo.has_subscribers(100).await.unwrap());
+}
+
+pub(super) async fn messages_since(repo: Repo) {
+    repo.store_message(message(100, 1, "old", Duration::days(5)))
+        .await
+        .unwrap();
+    repo.store_message(message(100, 2, "first", Duration::hours(2)))
+        .await
+        .unwrap();
+    repo.store_message(message(100, 2, "edited", Duration::hours(2)))
+        .await
+        .unwrap();
+    repo.store_message(message(200, 1, "second", Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.store_message(message(300, 1, "other", Duration::hours(1)))
+        .await
+        .unwrap();
+
+    let messages = repo
+        .get_messages_since(&[100, 200], Utc::now() - Duration::days(1), Utc::now(), 10)
+        .await
+        .unwrap();
+
+    let texts = messages.iter().map(|m| m.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(texts, vec!["second", "edited"]);
+
+    let messages = repo
+        .get_messages_since(
+            &[100, 200],
+            Utc::now() - Duration::days(1),
+            Utc::now() - Duration::minutes(90),
+            10,
+        )
+        .await
+        .unwrap();
+
+    let texts = messages.iter().map(|m| m.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(texts, vec!["edited"]);
+}
+
+pub(super) async fn search_messages(repo: Repo) {
+    for (channel_id, message_id, text, age) in [
+        (100, 1, "Central bank raises rates", Duration::hours(3)),
+        (100, 2, "Bank cuts rates", Duration::hours(1)),
+        (100, 3, "Weather: rain", Duration::hours(2)),
+        (
+            100,
+            4,
+            "Bank holiday weekend traffic is heavy",
+            Duration::days(10),
+        ),
+        (200, 1, "Bank of another channel", Duration::hours(1)),
+        (100, 5, "Новости: Банк снизил ставку", Duration::hours(4)),
+    ] {
+        repo.store_message(message(channel_id, message_id, text, age))
+            .await
+            .unwrap();
+    }
+
+    let search = |query: &str| MessageSearch {
+        query: query.to_string(),
+        channel_ids: vec![100],
+        since: Some(Utc::now() - Duration::days(1)),
+        until: None,
+        offset: 0,
+        limit: 10,
+    };
+    let ids = |page: SearchPage| {
+        page.messages
+            .iter()
+            .map(|m| m.message_id)
+            .collect::<Vec<_>>()
+    };
+
+    let page = repo
+        .search_messages(&search("bank raises rates?"))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(ids(page), vec![1, 2]);
+
+    let page = repo.search_messages(&search("банк")).await.unwrap();
+    assert_eq!(ids(page), vec![5]);
+
+    let page = repo
+        .search_messages(&MessageSearch {
+            offset: 1,
+            limit: 1,
+            ..search("bank")
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(ids(page), vec![1]);
+
+    let page = repo
+        .search_messages(&MessageSearch {
+            channel_ids: vec![100, 200],
+            since: None,
+            until: Some(Utc::now() - Duration::hours(2)),
+            ..search("bank")
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(page), vec![1, 4]);
+
+    let page = repo.search_messages(&search("a is")).await.unwrap();
+    assert_eq!(page.total, 0);
+    assert!(page.messages.is_empty());
+
+    repo.update_message_text(100, 1, "Central bank holds")
+        .await
+        .unwrap();
+    let page = repo.search_messages(&search("raises")).await.unwrap();
+    assert_eq!(page.total, 0);
+    let page = repo.search_messages(&search("holds")).await.unwrap();
+    assert_eq!(ids(page), vec![1]);
+
+    repo.delete_messages(100, &[5]).await.unwrap();
+    let page = repo.search_messages(&search("банк")).await.unwrap();
+    assert_eq!(page.total, 0);
+}
+
+pub(super) async fn summarize_time_and_users(repo: Repo) {
+    let default = repo.get_last_summarize_time(1).await.unwrap();
+    assert!(default < Utc::now() - Duration::days(2));
+
+    let at = Utc::now() - Duration::hours(1);
+    repo.update_summarize_time(1, at).await.unwrap();
+    let updated = repo.get_last_summarize_time(1).await.unwrap();
+    assert_eq!(updated.timestamp_millis(), at.timestamp_millis());
+
+    assert!(!repo.is_user_allowed(1).await.unwrap());
+    repo.set_user_allowed(1, true).await.unwrap();
+    assert!(repo.is_user_allowed(1).await.unwrap());
+    repo.set_user_allowed(1, false).await.unwrap();
+    assert!(!repo.is_user_allowed(1).await.unwrap());
+    assert_eq!(repo.get_users().await.unwrap().len(), 1);
+}
+
+pub(super) async fn inactive_users(repo: Repo) {
+    repo.set_user_allowed(1, true).await.unwrap();
+    repo.set_user_allowed(2, true).await.unwrap();
+    assert!(repo.get_users().await.unwrap().iter().all(|u| u.active));
+
+    assert!(repo.set_user_active(2, false).await.unwrap());
+    assert!(!repo.set_user_active(3, false).await.unwrap());
+
+    assert_eq!(repo.get_active_users(&[1, 2, 3]).await.unwrap(), vec![1, 3]);
+    assert!(repo.get_active_users(&[]).await.unwrap().is_empty());
+
+    // Access changes keep the flag
+    repo.set_user_allowed(2, true).await.unwrap();
+    assert_eq!(
+        repo.get_active_users(&[2]).await.unwrap(),
+        Vec::<i64>::new()
+    );
+
+    repo.set_user_active(2, true).await.unwrap();
+    assert_eq!(repo.get_active_users(&[2]).await.unwrap(), vec![2]);
+}
+
+pub(super) async fn messages_limit(repo: Repo) {
+    for message_id in 1..=5 {
+        repo.store_message(message(
+            100,
+            message_id,
+            &message_id.to_string(),
+            Duration::minutes(message_id.into()),
+        ))
+        .await
+        .unwrap();
+    }
+
+    let messages = repo
+        .get_messages_since(&[100], Utc::now() - Duration::days(1), Utc::now(), 2)
+        .await
+        .unwrap();
+
+    let ids = messages.iter().map(|m| m.message_id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 2]);
+
+    let messages = repo
+        .get_messages_since(&[], Utc::now() - Duration::days(1), Utc::now(), 2)
+        .await
+        .unwrap();
+    assert!(messages.is_empty());
+}
+
+pub(super) async fn update_subscription_handle(repo: Repo) {
+    repo.add_subscription(subscription(1, 100, "old"))
+        .await
+        .unwrap();
+    repo.add_subscription(subscription(2, 100, "old"))
+        .await
+        .unwrap();
+
+    repo.update_subscription_handle(100, "new").await.unwrap();
+
+    for user_id in [1, 2] {
+        let subs = repo.get_user_subscriptions(user_id).await.unwrap();
+        assert_eq!(subs[0].channel_handle, "new");
+    }
+}
+
+pub(super) async fn prune_messages(repo: Repo) {
+    for message_id in 1..=4 {
+        repo.store_message(message(
+            100,
+            message_id,
+            "text",
+            Duration::hours(message_id.into()),
+        ))
+        .await
+        .unwrap();
+    }
+    repo.store_message(message(200, 1, "text", Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.store_message(message(200, 2, "text", Duration::days(40)))
+        .await
+        .unwrap();
+
+    let config = RetentionConfig {
+        max_age_days: 30,
+        max_messages_per_channel: Some(2),
+        ..Default::default()
+    };
+
+    assert_eq!(repo.prune_messages(&config).await.unwrap(), 3);
+
+    let since = Utc::now() - Duration::days(365);
+    let remaining = repo
+        .get_messages_since(&[100, 200], since, Utc::now(), 10)
+        .await
+        .unwrap();
+    let mut keys = remaining
+        .iter()
+        .map(|m| (m.channel_id, m.message_id))
+        .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec![(100, 1), (100, 2), (200, 1)]);
+}
+
+pub(super) async fn summary_history(repo: Repo) {
+    let now = Utc::now();
+
+    for (user_id, age) in [(1, 3), (1, 1), (2, 0), (1, 2)] {
+        repo.store_summary(StoredSummary {
+            user_id,
+            since: now - Duration::days(1),
+            until: now,
+            channels: vec!["first".to_string(), "second".to_string()],
+            model: "model".to_string(),
+            input_tokens: 100,
+            output_tokens: age,
+            html: format!("<b>{age}</b>"),
+            created_at: now - Duration::hours(age),
+        })
+        .await
+        .unwrap();
+    }
+
+    let history = repo.get_user_summaries(1, 2).await.unwrap();
+
+    let html = history.iter().map(|s| s.html.as_str()).collect::<Vec<_>>();
+    assert_eq!(html, vec!["<b>1</b>", "<b>2</b>"]);
+    assert_eq!(history[0].channels, vec!["first", "second"]);
+    assert_eq!(history[0].output_tokens, 1);
+    assert_eq!(history[0].until.timestamp_millis(), now.timestamp_millis());
+
+    assert_eq!(repo.get_user_summaries(2, 10).await.unwrap().len(), 1);
+    assert!(repo.get_user_summaries(3, 10).await.unwrap().is_empty());
+}
+
+pub(super) async fn digest_schedules(repo: Repo) {
+    let now = Utc::now();
+
+    let schedule = |user_id, every_hours, next_run_at| DigestSchedule {
+        user_id,
+        every_hours,
+        minute_of_day: 9 * 60,
+        utc_offset_minutes: 180,
+        next_run_at,
+    };
+
+    repo.set_digest_schedule(schedule(1, None, now - Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.set_digest_schedule(schedule(2, Some(6), now + Duration::hours(1)))
+        .await
+        .unwrap();
+
+    let due = repo.get_due_digest_schedules(now).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].user_id, 1);
+    assert_eq!(due[0].every_hours, None);
+    assert_eq!(due[0].utc_offset_minutes, 180);
+
+    // Rescheduling replaces the previous entry
+    repo.set_digest_schedule(schedule(1, Some(12), now + Duration::hours(12)))
+        .await
+        .unwrap();
+    assert!(repo.get_due_digest_schedules(now).await.unwrap().is_empty());
+
+    let stored = repo.get_digest_schedule(1).await.unwrap().unwrap();
+    assert_eq!(stored.every_hours, Some(12));
+
+    assert!(repo.remove_digest_schedule(2).await.unwrap());
+    assert!(!repo.remove_digest_schedule(2).await.unwrap());
+    assert!(repo.get_digest_schedule(2).await.unwrap().is_none());
+}
+
+pub(super) async fn filters(repo: Repo) {
+    let rule = |user_id, channel_id, pattern: &str| FilterRule {
+        user_id,
+        channel_id,
+        exclude: false,
+        pattern: pattern.to_string(),
+        regex: false,
+        created_at: Utc::now(),
+    };
+
+    repo.add_filter(rule(1, None, "rates")).await.unwrap();
+    repo.add_filter(rule(1, Some(100), "bank")).await.unwrap();
+    repo.add_filter(rule(1, None, "rates")).await.unwrap();
+    repo.add_filter(FilterRule {
+        exclude: true,
+        ..rule(1, None, "rates")
+    })
+    .await
+    .unwrap();
+    repo.add_filter(rule(2, None, "weather")).await.unwrap();
+
+    let patterns = |rules: Vec<FilterRule>| {
+        rules
+            .into_iter()
+            .map(|r| (r.user_id, r.channel_id, r.exclude, r.pattern))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(patterns(repo.get_user_filters(1).await.unwrap()), vec![
+        (1, Some(100), false, "bank".to_string()),
+        (1, None, false, "rates".to_string()),
+        (1, None, true, "rates".to_string()),
+    ]);
+    assert_eq!(repo.get_filters_of_users(&[1, 2]).await.unwrap().len(), 4);
+    assert!(repo.get_filters_of_users(&[]).await.unwrap().is_empty());
+
+    assert!(
+        repo.remove_filter(&rule(1, Some(100), "bank"))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repo
+            .remove_filter(&rule(1, Some(100), "bank"))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repo
+            .remove_filter(&rule(1, Some(200), "rates"))
+            .await
+            .unwrap()
+    );
+
+    assert_eq!(repo.clear_filters(1).await.unwrap(), 2);
+    assert!(repo.get_user_filters(1).await.unwrap().is_empty());
+    assert_eq!(repo.get_user_filters(2).await.unwrap().len(), 1);
+}
+
+pub(super) async fn edit_and_delete_messages(repo: Repo) {
+    for message_id in 1..=3 {
+        repo.store_message(message(100, message_id, "original", Duration::hours(1)))
+            .await
+            .unwrap();
+    }
+
+    assert!(repo.update_message_text(100, 2, "edited").await.unwrap());
+    assert!(!repo.update_message_text(100, 9, "edited").await.unwrap());
+    assert!(!repo.update_message_text(200, 2, "edited").await.unwrap());
+
+    assert_eq!(repo.delete_messages(100, &[1, 9]).await.unwrap(), 1);
+    assert_eq!(repo.delete_messages(200, &[3]).await.unwrap(), 0);
+    assert_eq!(repo.delete_messages(100, &[]).await.unwrap(), 0);
+
+    let since = Utc::now() - Duration::days(1);
+    let mut texts = repo
+        .get_messages_since(&[100], since, Utc::now(), 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| (m.message_id, m.text))
+        .collect::<Vec<_>>();
+    texts.sort();
+    assert_eq!(texts, vec![
+        (2, "edited".to_string()),
+        (3, "original".to_string())
+    ]);
+}
+
+pub(super) async fn deliveries(repo: Repo) {
+    let sent = |message_id, user_id, chat_message_id, age| Delivery {
+        channel_id: 100,
+        message_id,
+        user_id,
+        chat_message_id: Some(chat_message_id),
+        status: DeliveryStatus::Sent,
+        attempts: 1,
+        last_error: None,
+        attempted_at: Utc::now() - age,
+    };
+
+    repo.record_delivery(sent(1, 2, 10, Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.record_delivery(sent(1, 1, 20, Duration::hours(2)))
+        .await
+        .unwrap();
+    repo.record_delivery(sent(2, 1, 21, Duration::days(40)))
+        .await
+        .unwrap();
+    repo.record_delivery(Delivery {
+        chat_message_id: None,
+        status: DeliveryStatus::Failed,
+        attempts: 3,
+        last_error: Some("Forbidden: bot was blocked by the user".to_string()),
+        ..sent(3, 1, 0, Duration::minutes(5))
+    })
+    .await
+    .unwrap();
+    // Redelivery replaces the previous attempt
+    repo.record_delivery(Delivery {
+        attempts: 2,
+        ..sent(1, 2, 11, Duration::hours(1))
+    })
+    .await
+    .unwrap();
+
+    let chat_messages = |deliveries: Vec<Delivery>| {
+        deliveries
+            .into_iter()
+            .map(|d| (d.user_id, d.chat_message_id))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        chat_messages(repo.get_post_deliveries(100, 1).await.unwrap()),
+        vec![(1, Some(20)), (2, Some(11))]
+    );
+    assert!(repo.get_post_deliveries(200, 1).await.unwrap().is_empty());
+
+    let delivery = repo.get_delivery(100, 1, 2).await.unwrap().unwrap();
+    assert_eq!(delivery.attempts, 2);
+    assert_eq!(delivery.status, DeliveryStatus::Sent);
+    assert!(repo.get_delivery(100, 1, 3).await.unwrap().is_none());
+
+    let failed = repo.get_delivery(100, 3, 1).await.unwrap().unwrap();
+    assert_eq!(failed.status, DeliveryStatus::Failed);
+    assert_eq!(failed.chat_message_id, None);
+    assert_eq!(failed.attempts, 3);
+    assert!(failed.last_error.unwrap().contains("blocked"));
+
+    let messages = |deliveries: Vec<Delivery>| {
+        deliveries
+            .into_iter()
+            .map(|d| d.message_id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages(repo.get_user_deliveries(1, 10).await.unwrap()),
+        vec![3, 1, 2]
+    );
+    assert_eq!(
+        messages(repo.get_user_deliveries(1, 1).await.unwrap()),
+        vec![3]
+    );
+
+    let before = Utc::now() - Duration::days(30);
+    assert_eq!(repo.delete_deliveries_before(before).await.unwrap(), 1);
+    assert!(repo.get_post_deliveries(100, 2).await.unwrap().is_empty());
+    assert_eq!(repo.get_post_deliveries(100, 1).await.unwrap().len(), 2);
+}
+
+pub(super) async fn event_queue(repo: Repo) {
+    for payload in ["a", "b", "c"] {
+        repo.enqueue_event(payload.to_string()).await.unwrap();
+    }
+
+    let payloads =
+        |events: Vec<QueuedEvent>| events.into_iter().map(|e| e.payload).collect::<Vec<_>>();
+
+    let due = repo.get_due_events(Utc::now(), 10).await.unwrap();
+    assert_eq!(payloads(due.clone()), vec!["a", "b", "c"]);
+    assert!(due.iter().all(|e| e.attempts == 0 && !e.dead));
+    assert_eq!(
+        payloads(repo.get_due_events(Utc::now(), 2).await.unwrap()),
+        vec!["a", "b"]
+    );
+
+    let (a, b, c) = (&due[0].id, &due[1].id, &due[2].id);
+
+    repo.retry_event(a, "timed out", Utc::now() + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(
+        payloads(repo.get_due_events(Utc::now(), 10).await.unwrap()),
+        vec!["b", "c"]
+    );
+
+    let later = repo
+        .get_due_events(Utc::now() + Duration::hours(2), 10)
+        .await
+        .unwrap();
+    assert_eq!(later[0].payload, "a");
+    assert_eq!(later[0].attempts, 1);
+    assert_eq!(later[0].last_error.as_deref(), Some("timed out"));
+
+    repo.dead_letter_event(b, "invalid payload").await.unwrap();
+    assert_eq!(
+        payloads(repo.get_due_events(Utc::now(), 10).await.unwrap()),
+        vec!["c"]
+    );
+    assert_eq!(repo.count_events(true).await.unwrap(), 1);
+    assert_eq!(repo.count_events(false).await.unwrap(), 2);
+
+    assert!(repo.ack_event(c).await.unwrap());
+    assert!(!repo.ack_event(c).await.unwrap());
+    assert!(!repo.ack_event("unknown").await.unwrap());
+    assert_eq!(repo.count_events(false).await.unwrap(), 1);
+}